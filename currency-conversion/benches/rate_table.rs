@@ -0,0 +1,81 @@
+//! Repeated conversions with a rate table built once vs the linear lookup it replaced
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use currency_conversion::common::{conversion_rate::ConversionRate, rate_table::RateTable};
+use rust_decimal::Decimal;
+
+const CURRENCIES: usize = 170;
+const CONVERSIONS: usize = 2000;
+
+/// Rates from EUR to 169 currencies
+fn conversion_rates() -> Vec<ConversionRate> {
+    (1..CURRENCIES)
+        .map(|i| ConversionRate {
+            from: "EUR".to_string(),
+            to: format!("C{i:03}"),
+            rate: Decimal::new(i as i64 * 37 + 100, 2),
+        })
+        .collect()
+}
+
+/// Pairs of currencies to convert (none is EUR)
+fn pairs() -> Vec<(String, String)> {
+    (0..CONVERSIONS)
+        .map(|i| {
+            (
+                format!("C{:03}", i % (CURRENCIES - 1) + 1),
+                format!("C{:03}", (i * 7) % (CURRENCIES - 1) + 1),
+            )
+        })
+        .collect()
+}
+
+/// Rate `from` a currency `to` another with a linear `filter` over the conversion rates for each leg
+/// (copy of the lookup used before [`RateTable`])
+fn linear_conversion_rate(
+    base: &str,
+    conversion_rates: &[ConversionRate],
+    from: &str,
+    to: &str,
+) -> Option<Decimal> {
+    if to == base {
+        let rate = conversion_rates.iter().find(|rate| rate.to == from)?;
+        Some(Decimal::ONE / rate.rate)
+    } else if from == base {
+        let rate = conversion_rates.iter().find(|rate| rate.to == to)?;
+        Some(rate.rate)
+    } else {
+        let rate_from = linear_conversion_rate(base, conversion_rates, base, from)?;
+        let rate_to = linear_conversion_rate(base, conversion_rates, base, to)?;
+        Some(rate_to / rate_from)
+    }
+}
+
+fn bench_conversions(c: &mut Criterion) {
+    let conversion_rates = conversion_rates();
+    let pairs = pairs();
+
+    let mut group = c.benchmark_group("2000 conversions over 170 currencies");
+
+    group.bench_function("linear lookup", |b| {
+        b.iter(|| {
+            for (from, to) in &pairs {
+                black_box(linear_conversion_rate("EUR", &conversion_rates, from, to).unwrap());
+            }
+        })
+    });
+
+    group.bench_function("rate table", |b| {
+        b.iter(|| {
+            let table = RateTable::build("EUR", &conversion_rates);
+            for (from, to) in &pairs {
+                black_box(table.resolve(from, to).unwrap());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_conversions);
+criterion_main!(benches);
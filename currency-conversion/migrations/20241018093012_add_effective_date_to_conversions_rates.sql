@@ -0,0 +1,6 @@
+-- conversions rates are kept for each effective date (history)
+ALTER TABLE conversions_rates
+    ADD COLUMN effective_date date NOT NULL DEFAULT CURRENT_DATE;
+
+CREATE INDEX IF NOT EXISTS conversions_rates_effective_date_idx
+    ON conversions_rates (effective_date);
@@ -0,0 +1,10 @@
+-- one conversion rate by pair and effective date (needed by upserts)
+DELETE FROM conversions_rates a
+    USING conversions_rates b
+    WHERE a.ctid < b.ctid
+        AND a."from" = b."from"
+        AND a."to" = b."to"
+        AND a.effective_date = b.effective_date;
+
+ALTER TABLE conversions_rates
+    ADD CONSTRAINT conversions_rates_pair_effective_date_key UNIQUE ("from", "to", effective_date);
@@ -0,0 +1,7 @@
+-- table symbols
+CREATE TABLE IF NOT EXISTS symbols
+(
+    code TEXT NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (code)
+);
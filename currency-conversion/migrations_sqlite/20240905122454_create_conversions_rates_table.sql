@@ -0,0 +1,7 @@
+-- table conversions rates (rate is stored as text to keep decimal precision)
+CREATE TABLE IF NOT EXISTS conversions_rates
+(
+    "from" TEXT NOT NULL,
+    "to" TEXT NOT NULL,
+    rate TEXT NOT NULL
+);
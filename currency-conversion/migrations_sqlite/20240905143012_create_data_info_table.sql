@@ -0,0 +1,7 @@
+-- table data info (last_update_date is a unix timestamp)
+CREATE TABLE IF NOT EXISTS data_info
+(
+    data_name TEXT NOT NULL,
+    last_update_date INTEGER NOT NULL,
+    PRIMARY KEY (data_name)
+);
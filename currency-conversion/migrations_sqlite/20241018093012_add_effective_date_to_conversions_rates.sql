@@ -0,0 +1,8 @@
+-- conversions rates are kept for each effective date (history, stored as YYYY-MM-DD)
+ALTER TABLE conversions_rates
+    ADD COLUMN effective_date TEXT NOT NULL DEFAULT '1970-01-01';
+
+UPDATE conversions_rates SET effective_date = date('now');
+
+CREATE INDEX IF NOT EXISTS conversions_rates_effective_date_idx
+    ON conversions_rates (effective_date);
@@ -1,2 +1,7 @@
+pub mod conversion_path;
 pub mod conversion_rate;
+pub mod iso4217;
+pub mod money_format;
+pub mod rate_table;
+pub mod secret;
 pub mod supported_symbols;
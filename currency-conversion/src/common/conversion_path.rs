@@ -0,0 +1,96 @@
+use crate::error::Result;
+
+use super::{conversion_rate::ConversionRate, rate_table::RateTable};
+
+/// Conversion rate resolved through a path of stored conversion rates
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResolvedRate {
+    pub rate: ConversionRate,
+    /// currencies from origin to destination (e.g. ["USD", "EUR", "JPY"])
+    pub path: Vec<String>,
+}
+
+/// Resolve the conversion rate `from` a currency `to` another through stored conversion rates, whatever their origin currency
+/// (see [`RateTable::resolve`], build a [`RateTable`] once for repeated lookups)
+pub fn resolve_conversion_rate(
+    base: &str,
+    conversion_rates: &[ConversionRate],
+    from: &str,
+    to: &str,
+) -> Result<ResolvedRate> {
+    RateTable::build(base, conversion_rates).resolve(from, to)
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal_macros::dec;
+
+    use crate::common::conversion_rate::ConversionRate;
+
+    fn rate(from: &str, to: &str, rate: rust_decimal::Decimal) -> ConversionRate {
+        ConversionRate {
+            from: from.to_string(),
+            to: to.to_string(),
+            rate,
+        }
+    }
+
+    #[test]
+    fn resolve_conversion_rate_mixed_bases() {
+        // rates from two sources quoted against different bases
+        let data = vec![
+            rate("USD", "JPY", dec!(150)),
+            rate("USD", "CAD", dec!(1.25)),
+            rate("EUR", "USD", dec!(1.08)),
+            rate("EUR", "GBP", dec!(0.84)),
+        ];
+
+        let res = super::resolve_conversion_rate("EUR", &data, "GBP", "JPY");
+
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        assert_eq!(res.path, vec!["GBP", "EUR", "USD", "JPY"]);
+        assert_eq!(res.rate.rate, dec!(1.08) * dec!(150) / dec!(0.84));
+
+        let res = super::resolve_conversion_rate("EUR", &data, "JPY", "CAD").unwrap();
+        assert_eq!(res.path, vec!["JPY", "USD", "CAD"]);
+        assert_eq!(res.rate.rate, dec!(1.25) / dec!(150));
+
+        let res = super::resolve_conversion_rate("EUR", &data, "CAD", "CAD").unwrap();
+        assert_eq!(res.path, vec!["CAD"]);
+        assert_eq!(res.rate.rate, dec!(1));
+    }
+
+    #[test]
+    fn resolve_conversion_rate_prefer_base() {
+        let data = vec![
+            rate("USD", "JPY", dec!(150)),
+            rate("USD", "GBP", dec!(0.78)),
+            rate("EUR", "JPY", dec!(162)),
+            rate("EUR", "GBP", dec!(0.84)),
+        ];
+
+        let res = super::resolve_conversion_rate("EUR", &data, "GBP", "JPY").unwrap();
+
+        assert_eq!(res.path, vec!["GBP", "EUR", "JPY"]);
+    }
+
+    #[test]
+    fn resolve_conversion_rate_no_path() {
+        let data = vec![
+            rate("EUR", "USD", dec!(1.08)),
+            rate("GBP", "JPY", dec!(190)),
+        ];
+
+        let res = super::resolve_conversion_rate("EUR", &data, "USD", "JPY");
+        assert!(res.is_err());
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "No conversion path from USD to JPY !"
+        );
+
+        let res = super::resolve_conversion_rate("EUR", &data, "USD", "TEST");
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "TEST symbols not found !");
+    }
+}
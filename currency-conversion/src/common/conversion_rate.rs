@@ -3,12 +3,14 @@ use std::collections::HashMap;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-use anyhow::{bail, Result};
+use crate::error::Result;
 
 use crate::list::list_data::ListDataItem;
 
+use super::conversion_path::resolve_conversion_rate;
+
 /// Conversion Rates from a currency to another
-#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, PartialOrd, Eq, Ord,sqlx::FromRow)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, PartialOrd, Eq, Ord, sqlx::FromRow)]
 pub struct ConversionRate {
     pub from: String,
     pub to: String,
@@ -23,56 +25,15 @@ impl ListDataItem for ConversionRate {
 }
 
 impl ConversionRate {
-    /// Get conversion rate from a currency ([`from`]) to another ([`to`]) (from [`conversion_rates`] provided)
+    /// Get conversion rate from a currency ([`from`]) to another ([`to`]) (from [`conversion_rates`] provided),
+    /// resolved through any path of conversion rates (see [`resolve_conversion_rate`])
     pub fn get_conversion_rate(
         base: &str,
-        conversion_rates: &Vec<ConversionRate>,
+        conversion_rates: &[ConversionRate],
         from: &str,
         to: &str,
     ) -> Result<ConversionRate> {
-        let res: ConversionRate;
-
-        // if [`to`] == [`base`] then we search a rate which convert to [`from`] and return
-        // inverted rate
-        if to == base {
-            let mut search_iter = conversion_rates.iter().filter(|rate| rate.to == from);
-            let search_result = search_iter.next();
-
-            if search_result.is_none() {
-                bail!("{from} symbols not found !");
-            }
-            res = ConversionRate {
-                from: from.to_string(),
-                to: to.to_string(),
-                rate: Decimal::new(1, 0) / search_result.unwrap().rate,
-            };
-        // if [`from`] == [`base`] then we search a rate which convert to [`to`]
-        } else if from == base {
-            let mut search_iter = conversion_rates.iter().filter(|rate| rate.to == to);
-
-            let search_result = search_iter.next();
-
-            if search_result.is_none() {
-                bail!("{to} symbols not found !");
-            }
-            res = search_result.unwrap().clone();
-        // else we search to conversion rates :
-        // - rates1 : from [`base`] to [`from`]
-        // - rate2 : from [`base`] to [`to`]
-        // and we return rate2/rate1
-        } else {
-            let rate_from =
-                ConversionRate::get_conversion_rate(base, conversion_rates, base, from)?;
-            let rate_to = ConversionRate::get_conversion_rate(base, conversion_rates, base, to)?;
-
-            res = ConversionRate {
-                from: from.to_string(),
-                to: to.to_string(),
-                rate: rate_to.rate / rate_from.rate,
-            };
-        }
-
-        Ok(res)
+        Ok(resolve_conversion_rate(base, conversion_rates, from, to)?.rate)
     }
 }
 
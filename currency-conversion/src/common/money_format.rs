@@ -0,0 +1,296 @@
+use std::str::FromStr;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::error::{Error, Result};
+
+use super::iso4217::minor_units;
+
+/// How an amount of money is written
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum MoneyFormat {
+    /// bare decimal (e.g. "1234.56")
+    #[default]
+    Plain,
+    /// currency symbol (e.g. "$1,234.56")
+    Symbol,
+    /// currency code (e.g. "USD 1,234.56")
+    Code,
+    /// conventions of a locale (e.g. "1 234,56 €" for fr-FR)
+    Locale(String),
+}
+
+impl FromStr for MoneyFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "plain" => MoneyFormat::Plain,
+            "symbol" => MoneyFormat::Symbol,
+            "code" => MoneyFormat::Code,
+            _ => match s.strip_prefix("locale:") {
+                Some(tag) => {
+                    // check the locale is supported
+                    LocaleConventions::get(tag)?;
+                    MoneyFormat::Locale(tag.to_string())
+                }
+                None => {
+                    return Err(Error::Parse(format!(
+                        "\"{s}\" isn't a money format (plain, symbol, code or locale:<tag>)"
+                    )))
+                }
+            },
+        })
+    }
+}
+
+/// Where the currency symbol is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SymbolPosition {
+    /// "$1,234.56"
+    Prefix,
+    /// "€ 1.234,56"
+    PrefixWithSpace,
+    /// "1 234,56 €"
+    SuffixWithSpace,
+}
+
+/// Money writing conventions of a locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LocaleConventions {
+    grouping_separator: &'static str,
+    decimal_separator: &'static str,
+    symbol_position: SymbolPosition,
+}
+
+/// Conventions of supported locales (tag or language)
+const LOCALES: [(&str, LocaleConventions); 12] = [
+    (
+        "en",
+        LocaleConventions::new(",", ".", SymbolPosition::Prefix),
+    ),
+    (
+        "en-IE",
+        LocaleConventions::new(",", ".", SymbolPosition::Prefix),
+    ),
+    (
+        "fr",
+        LocaleConventions::new(" ", ",", SymbolPosition::SuffixWithSpace),
+    ),
+    (
+        "fr-CH",
+        LocaleConventions::new(" ", ".", SymbolPosition::SuffixWithSpace),
+    ),
+    (
+        "de",
+        LocaleConventions::new(".", ",", SymbolPosition::SuffixWithSpace),
+    ),
+    (
+        "de-CH",
+        LocaleConventions::new("’", ".", SymbolPosition::PrefixWithSpace),
+    ),
+    (
+        "es",
+        LocaleConventions::new(".", ",", SymbolPosition::SuffixWithSpace),
+    ),
+    (
+        "it",
+        LocaleConventions::new(".", ",", SymbolPosition::SuffixWithSpace),
+    ),
+    (
+        "nl",
+        LocaleConventions::new(".", ",", SymbolPosition::PrefixWithSpace),
+    ),
+    (
+        "pt",
+        LocaleConventions::new(".", ",", SymbolPosition::PrefixWithSpace),
+    ),
+    (
+        "ja",
+        LocaleConventions::new(",", ".", SymbolPosition::Prefix),
+    ),
+    (
+        "zh",
+        LocaleConventions::new(",", ".", SymbolPosition::Prefix),
+    ),
+];
+
+impl LocaleConventions {
+    const fn new(
+        grouping_separator: &'static str,
+        decimal_separator: &'static str,
+        symbol_position: SymbolPosition,
+    ) -> LocaleConventions {
+        LocaleConventions {
+            grouping_separator,
+            decimal_separator,
+            symbol_position,
+        }
+    }
+
+    /// Conventions of a locale tag (e.g. "fr-FR"), the language conventions are used if the tag isn't known
+    fn get(tag: &str) -> Result<LocaleConventions> {
+        let tag = tag.replace('_', "-");
+        let language = tag.split('-').next().unwrap_or_default();
+
+        LOCALES
+            .iter()
+            .find(|(locale, _)| locale.eq_ignore_ascii_case(&tag))
+            .or(LOCALES
+                .iter()
+                .find(|(locale, _)| locale.eq_ignore_ascii_case(language)))
+            .map(|(_, conventions)| *conventions)
+            .ok_or(Error::Parse(format!("Locale \"{tag}\" isn't supported !")))
+    }
+}
+
+/// Symbols of common currencies (others currencies are written with their code)
+const SYMBOLS: [(&str, &str); 24] = [
+    ("USD", "$"),
+    ("EUR", "€"),
+    ("GBP", "£"),
+    ("JPY", "¥"),
+    ("CNY", "¥"),
+    ("INR", "₹"),
+    ("KRW", "₩"),
+    ("RUB", "₽"),
+    ("TRY", "₺"),
+    ("ILS", "₪"),
+    ("VND", "₫"),
+    ("NGN", "₦"),
+    ("PHP", "₱"),
+    ("UAH", "₴"),
+    ("THB", "฿"),
+    ("BRL", "R$"),
+    ("CAD", "CA$"),
+    ("AUD", "A$"),
+    ("NZD", "NZ$"),
+    ("HKD", "HK$"),
+    ("MXN", "MX$"),
+    ("TWD", "NT$"),
+    ("CHF", "CHF"),
+    ("PLN", "zł"),
+];
+
+/// Symbol of a currency (its code if it has no known symbol)
+fn symbol(currency: &str) -> &str {
+    SYMBOLS
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(currency))
+        .map(|(_, symbol)| *symbol)
+        .unwrap_or(currency)
+}
+
+/// Write an amount of `currency` in `format` with `decimals` decimals (default : minor units of the currency,
+/// the decimals of `value` are kept for currencies without minor units)
+pub fn format_money(
+    value: Decimal,
+    currency: &str,
+    decimals: Option<u32>,
+    format: &MoneyFormat,
+) -> Result<String> {
+    let (conventions, unit) = match format {
+        MoneyFormat::Plain => return Ok(value.to_string()),
+        MoneyFormat::Symbol => (LocaleConventions::get("en")?, symbol(currency)),
+        MoneyFormat::Code => (
+            LocaleConventions::new(",", ".", SymbolPosition::PrefixWithSpace),
+            currency,
+        ),
+        MoneyFormat::Locale(tag) => (LocaleConventions::get(tag)?, symbol(currency)),
+    };
+
+    let decimals = decimals.or(minor_units(currency)).unwrap_or(value.scale());
+    let mut value = value.round_dp_with_strategy(decimals, RoundingStrategy::MidpointNearestEven);
+    value.rescale(decimals);
+
+    let digits = value.abs().to_string();
+    let (integer, fraction) = match digits.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (digits.as_str(), None),
+    };
+
+    let mut number = group_digits(integer, conventions.grouping_separator);
+    if let Some(fraction) = fraction {
+        number.push_str(conventions.decimal_separator);
+        number.push_str(fraction);
+    }
+
+    let sign = if value.is_sign_negative() && !value.is_zero() {
+        "-"
+    } else {
+        ""
+    };
+
+    Ok(match conventions.symbol_position {
+        SymbolPosition::Prefix => format!("{sign}{unit}{number}"),
+        SymbolPosition::PrefixWithSpace => format!("{sign}{unit} {number}"),
+        SymbolPosition::SuffixWithSpace => format!("{sign}{number} {unit}"),
+    })
+}
+
+/// Insert `separator` between each group of 3 digits
+fn group_digits(integer: &str, separator: &str) -> String {
+    let mut res = String::new();
+
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i).is_multiple_of(3) {
+            res.push_str(separator);
+        }
+        res.push(digit);
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal_macros::dec;
+
+    use super::MoneyFormat;
+
+    #[test]
+    fn format_money() {
+        let format = |value, currency, format: &str| {
+            super::format_money(
+                value,
+                currency,
+                None,
+                &format.parse::<MoneyFormat>().unwrap(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(format(dec!(1234.56), "EUR", "plain"), "1234.56");
+        assert_eq!(format(dec!(1234.56), "USD", "symbol"), "$1,234.56");
+        assert_eq!(format(dec!(1234.5), "JPY", "symbol"), "¥1,234");
+        assert_eq!(format(dec!(1234.56), "JPY", "symbol"), "¥1,235");
+        assert_eq!(format(dec!(1234.56), "USD", "code"), "USD 1,234.56");
+        assert_eq!(format(dec!(1234.56), "EUR", "locale:fr-FR"), "1 234,56 €");
+        assert_eq!(
+            format(dec!(1234567.8), "EUR", "locale:de"),
+            "1.234.567,80 €"
+        );
+        assert_eq!(format(dec!(1234.56), "CHF", "locale:de-CH"), "CHF 1’234.56");
+        assert_eq!(format(dec!(-12.5), "USD", "locale:en-US"), "-$12.50");
+        assert_eq!(format(dec!(999), "KWD", "code"), "KWD 999.000");
+        // unknown currency : decimals kept, code as symbol
+        assert_eq!(format(dec!(0.00123), "BTC", "symbol"), "BTC0.00123");
+
+        // explicit decimals
+        let format = |value, decimals| {
+            super::format_money(value, "USD", Some(decimals), &MoneyFormat::Symbol).unwrap()
+        };
+        assert_eq!(format(dec!(1234.5678), 4), "$1,234.5678");
+        assert_eq!(format(dec!(1234.5678), 0), "$1,235");
+    }
+
+    #[test]
+    fn money_format_from_str() {
+        assert_eq!(
+            "locale:fr_FR".parse::<MoneyFormat>().unwrap(),
+            MoneyFormat::Locale("fr_FR".to_string())
+        );
+        assert!("locale:xx-XX".parse::<MoneyFormat>().is_err());
+        assert!("fancy".parse::<MoneyFormat>().is_err());
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::{HashMap, VecDeque};
+
+use rust_decimal::Decimal;
+
+use crate::{
+    error::{Error, Result},
+    storage::common::StorageManager,
+};
+
+use super::{conversion_path::ResolvedRate, conversion_rate::ConversionRate};
+
+/// Conversion between two currencies of a [`RateTable`] with a stored rate (used as is or inverted)
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    from: usize,
+    to: usize,
+    rate: Decimal,
+    inverted: bool,
+}
+
+/// Conversion rates indexed by currency, built once for repeated lookups.
+/// Stored rates and their inverses are indexed by pair of currencies : conversions with a stored rate or through `base` are resolved in O(1),
+/// others conversions are resolved through the path with the fewest conversions.
+#[derive(Debug, Clone, Default)]
+pub struct RateTable {
+    /// currency quoting most rates, preferred as intermediate currency
+    base: String,
+    /// index of each currency
+    index: HashMap<String, usize>,
+    currencies: Vec<String>,
+    /// conversions from each currency, direct rates first
+    edges: Vec<Vec<Edge>>,
+    /// conversion of each pair of currencies (direct rate preferred over inverted rate)
+    pairs: HashMap<(usize, usize), Edge>,
+}
+
+impl RateTable {
+    /// Build the table of `conversion_rates` (quoted from `base` or from any other currency)
+    pub fn build(base: &str, conversion_rates: &[ConversionRate]) -> RateTable {
+        let mut table = RateTable {
+            base: base.to_string(),
+            ..Default::default()
+        };
+
+        for rate in conversion_rates {
+            let from = table.get_or_insert(&rate.from);
+            let to = table.get_or_insert(&rate.to);
+
+            let direct = Edge {
+                from,
+                to,
+                rate: rate.rate,
+                inverted: false,
+            };
+            let inverse = Edge {
+                from: to,
+                to: from,
+                rate: rate.rate,
+                inverted: true,
+            };
+
+            table.edges[from].push(direct);
+            table.edges[to].push(inverse);
+            // first direct rate of a pair is kept, inverted rates are only used without direct rate
+            for edge in [direct, inverse] {
+                table
+                    .pairs
+                    .entry((edge.from, edge.to))
+                    .and_modify(|current| {
+                        if current.inverted && !edge.inverted {
+                            *current = edge
+                        }
+                    })
+                    .or_insert(edge);
+            }
+        }
+
+        // direct rates first
+        for edges in table.edges.iter_mut() {
+            edges.sort_by_key(|edge| edge.inverted);
+        }
+
+        table
+    }
+
+    /// Build the table of all conversion rates stored
+    pub async fn from_storage<T>(storage_manager: &T, base: &str) -> Result<RateTable>
+    where
+        T: StorageManager<ConversionRate>,
+    {
+        let conversion_rates = storage_manager.get_all().await?;
+
+        Ok(RateTable::build(base, &conversion_rates))
+    }
+
+    /// Currency quoting most rates
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// Number of currencies in the table
+    pub fn len(&self) -> usize {
+        self.currencies.len()
+    }
+
+    /// Indicate if the table has no conversion rate
+    pub fn is_empty(&self) -> bool {
+        self.currencies.is_empty()
+    }
+
+    /// Resolve the conversion rate `from` a currency `to` another.
+    /// The path with the fewest conversions is used, among them the path through `base` is preferred
+    /// and direct rates are preferred over inverted rates.
+    pub fn resolve(&self, from: &str, to: &str) -> Result<ResolvedRate> {
+        for currency in [from, to] {
+            if currency != self.base && !self.index.contains_key(currency) {
+                return Err(Error::UnknownCurrency(currency.to_string()));
+            }
+        }
+
+        let steps = self
+            .find_steps(from, to)
+            .ok_or_else(|| Error::NoConversionPath {
+                from: from.to_string(),
+                to: to.to_string(),
+            })?;
+
+        // divide once to limit rounding
+        let invalid_rate = || Error::InvalidConversionRate {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        let mut numerator = Decimal::ONE;
+        let mut denominator = Decimal::ONE;
+        for step in &steps {
+            let product = match step.inverted {
+                true => &mut denominator,
+                false => &mut numerator,
+            };
+            *product = product.checked_mul(step.rate).ok_or_else(invalid_rate)?;
+        }
+        let rate = numerator
+            .checked_div(denominator)
+            .ok_or_else(invalid_rate)?;
+
+        let path = std::iter::once(from.to_string())
+            .chain(steps.iter().map(|step| self.currencies[step.to].clone()))
+            .collect();
+
+        Ok(ResolvedRate {
+            rate: ConversionRate {
+                from: from.to_string(),
+                to: to.to_string(),
+                rate,
+            },
+            path,
+        })
+    }
+
+    fn get_or_insert(&mut self, currency: &str) -> usize {
+        match self.index.get(currency) {
+            Some(index) => *index,
+            None => {
+                let index = self.currencies.len();
+                self.index.insert(currency.to_string(), index);
+                self.currencies.push(currency.to_string());
+                self.edges.push(Vec::new());
+                index
+            }
+        }
+    }
+
+    /// Conversions from `from` to `to`
+    fn find_steps(&self, from: &str, to: &str) -> Option<Vec<Edge>> {
+        if from == to {
+            return Some(Vec::new());
+        }
+
+        let from = *self.index.get(from)?;
+        let to = *self.index.get(to)?;
+
+        // stored rate
+        if let Some(edge) = self.pairs.get(&(from, to)) {
+            return Some(vec![*edge]);
+        }
+
+        // through base (shortest path without stored rate)
+        if let Some(base) = self.index.get(&self.base) {
+            if let (Some(to_base), Some(from_base)) =
+                (self.pairs.get(&(from, *base)), self.pairs.get(&(*base, to)))
+            {
+                return Some(vec![*to_base, *from_base]);
+            }
+        }
+
+        let shortest = self.find_path(from, to)?;
+
+        // prefer the path through base if it is as short
+        let through_base = self.index.get(&self.base).and_then(|base| {
+            let mut steps = self.find_path(from, *base)?;
+            steps.extend(self.find_path(*base, to)?);
+            Some(steps)
+        });
+
+        match through_base {
+            Some(steps) if steps.len() == shortest.len() => Some(steps),
+            _ => Some(shortest),
+        }
+    }
+
+    /// Find the path with the fewest conversions (breadth first search)
+    fn find_path(&self, from: usize, to: usize) -> Option<Vec<Edge>> {
+        if from == to {
+            return Some(Vec::new());
+        }
+
+        let mut previous: Vec<Option<Edge>> = vec![None; self.currencies.len()];
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(currency) = queue.pop_front() {
+            for edge in &self.edges[currency] {
+                if edge.to == from || previous[edge.to].is_some() {
+                    continue;
+                }
+                previous[edge.to] = Some(*edge);
+
+                if edge.to == to {
+                    let mut steps = vec![*edge];
+                    while let Some(step) = previous[steps.last()?.from] {
+                        steps.push(step);
+                    }
+                    steps.reverse();
+                    return Some(steps);
+                }
+
+                queue.push_back(edge.to);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal_macros::dec;
+
+    use crate::common::conversion_rate::ConversionRate;
+
+    use super::RateTable;
+
+    fn rate(from: &str, to: &str, rate: rust_decimal::Decimal) -> ConversionRate {
+        ConversionRate {
+            from: from.to_string(),
+            to: to.to_string(),
+            rate,
+        }
+    }
+
+    #[test]
+    fn resolve() {
+        let table = RateTable::build(
+            "EUR",
+            &[
+                rate("EUR", "USD", dec!(1.08)),
+                rate("EUR", "GBP", dec!(0.84)),
+                rate("USD", "JPY", dec!(150)),
+                rate("GBP", "EUR", dec!(1.2)),
+            ],
+        );
+
+        assert_eq!(table.len(), 4);
+
+        // stored rate (direct rate preferred over inverted rate)
+        let res = table.resolve("GBP", "EUR").unwrap();
+        assert_eq!(res.rate.rate, dec!(1.2));
+
+        // inverted rate
+        let res = table.resolve("USD", "EUR").unwrap();
+        assert_eq!(res.rate.rate, dec!(1) / dec!(1.08));
+        assert_eq!(res.path, vec!["USD", "EUR"]);
+
+        // through base
+        let res = table.resolve("USD", "GBP").unwrap();
+        assert_eq!(res.rate.rate, dec!(0.84) / dec!(1.08));
+        assert_eq!(res.path, vec!["USD", "EUR", "GBP"]);
+
+        // through an other currency
+        let res = table.resolve("EUR", "JPY").unwrap();
+        assert_eq!(res.rate.rate, dec!(162));
+        assert_eq!(res.path, vec!["EUR", "USD", "JPY"]);
+
+        assert!(table.resolve("EUR", "XXX").is_err());
+    }
+
+    #[test]
+    fn resolve_empty() {
+        let table = RateTable::build("EUR", &[]);
+
+        assert!(table.is_empty());
+        assert_eq!(table.resolve("EUR", "EUR").unwrap().rate.rate, dec!(1));
+        assert!(table.resolve("EUR", "USD").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{Error, Result};
+
+/// Text shown in place of a secret value
+const REDACTED: &str = "********";
+
+/// Secret value (API key, password) configured in plaintext or as a reference resolved at load time :
+/// - `file:<path>` : content of the file (e.g. `file:/run/secrets/api_key`)
+/// - `env:<variable>` : value of the environment variable
+/// - `cmd:<command>` : output of the shell command (e.g. `cmd:pass show fx`)
+///
+/// Only the configured value is (de)serialized, the secret value is never displayed.
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct Secret {
+    /// value as written in config (plaintext or reference)
+    configured: String,
+    /// value of the reference once resolved
+    resolved: Option<String>,
+}
+
+impl Secret {
+    /// Build a secret from its configured value (plaintext or reference)
+    pub fn new(configured: &str) -> Secret {
+        Secret {
+            configured: configured.to_string(),
+            resolved: None,
+        }
+    }
+
+    /// Value as written in config
+    pub fn configured(&self) -> &str {
+        &self.configured
+    }
+
+    /// Indicate if the configured value is a reference (`file:`, `env:` or `cmd:`)
+    pub fn is_reference(&self) -> bool {
+        ["file:", "env:", "cmd:"]
+            .iter()
+            .any(|prefix| self.configured.starts_with(prefix))
+    }
+
+    /// Resolve the reference (if not already done)
+    pub fn resolve(&mut self) -> Result<()> {
+        if self.resolved.is_some() || !self.is_reference() {
+            return Ok(());
+        }
+
+        let error = |e: &dyn std::fmt::Display| {
+            Error::InvalidSettings(format!(
+                "Secret \"{}\" can't be resolved : {e}",
+                self.configured
+            ))
+        };
+
+        let value = if let Some(path) = self.configured.strip_prefix("file:") {
+            std::fs::read_to_string(path).map_err(|e| error(&e))?
+        } else if let Some(variable) = self.configured.strip_prefix("env:") {
+            std::env::var(variable).map_err(|e| error(&e))?
+        } else if let Some(command) = self.configured.strip_prefix("cmd:") {
+            let output = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .output()
+                .map_err(|e| error(&e))?;
+            if !output.status.success() {
+                return Err(error(&format!("command failed ({})", output.status)));
+            }
+            String::from_utf8(output.stdout).map_err(|e| error(&e))?
+        } else {
+            unreachable!()
+        };
+
+        self.resolved = Some(value.trim_end_matches(['\r', '\n']).to_string());
+
+        Ok(())
+    }
+
+    /// Secret value (configured value if it isn't a reference or isn't resolved yet)
+    pub fn expose(&self) -> &str {
+        self.resolved.as_deref().unwrap_or(&self.configured)
+    }
+
+    /// Copy safe to display : references are kept, plaintext values are redacted
+    pub fn redacted(&self) -> Secret {
+        match self.is_reference() {
+            true => Secret::new(&self.configured),
+            false => Secret::new(REDACTED),
+        }
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Secret::new(value)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({self})")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.redacted().configured)
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.configured)
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Secret::new(&String::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Secret;
+
+    #[test]
+    fn resolve() {
+        let dirpath = "./temp/test/common/secret/resolve/";
+        std::fs::create_dir_all(dirpath).unwrap();
+        let path = dirpath.to_string() + "api_key";
+        std::fs::write(&path, "123\n").unwrap();
+
+        let mut secret = Secret::new(&format!("file:{path}"));
+        assert!(secret.resolve().is_ok());
+        assert_eq!(secret.expose(), "123");
+
+        let mut secret = Secret::new("cmd:echo 456");
+        assert!(secret.resolve().is_ok());
+        assert_eq!(secret.expose(), "456");
+
+        let mut secret = Secret::new("plaintext");
+        assert!(secret.resolve().is_ok());
+        assert_eq!(secret.expose(), "plaintext");
+
+        let mut secret = Secret::new("env:CURRENCY_CONVERSION_TEST_UNDEFINED_VARIABLE");
+        let res = secret.resolve();
+        assert!(res.is_err());
+        assert!(res
+            .unwrap_err()
+            .to_string()
+            .contains("env:CURRENCY_CONVERSION_TEST_UNDEFINED_VARIABLE"));
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+
+    #[test]
+    fn redacted() {
+        let mut secret = Secret::new("cmd:echo 456");
+        secret.resolve().unwrap();
+
+        assert_eq!(format!("{secret:?}"), "Secret(cmd:echo 456)");
+        assert_eq!(serde_json::to_string(&secret).unwrap(), "\"cmd:echo 456\"");
+
+        let secret = Secret::new("plaintext");
+        assert_eq!(format!("{secret:?}"), "Secret(********)");
+        assert_eq!(secret.to_string(), "********");
+        assert_eq!(secret.redacted().configured(), "********");
+    }
+}
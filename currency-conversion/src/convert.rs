@@ -1 +1,3 @@
+pub mod convert_batch;
 pub mod convert_currency;
+pub mod rounding;
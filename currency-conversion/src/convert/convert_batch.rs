@@ -0,0 +1,338 @@
+use std::{
+    io::{Read, Write},
+    str::FromStr,
+};
+
+use rust_decimal::Decimal;
+
+use crate::{
+    common::rate_table::RateTable,
+    error::{Error, Result},
+};
+
+use super::rounding::Rounding;
+
+/// Origin currency of the rows of a batch
+#[derive(Debug, Clone)]
+pub enum BatchFrom {
+    /// Same currency for all rows
+    Currency(String),
+    /// Currency read in a column of each row
+    Column(String),
+}
+
+/// Settings of a batch conversion
+#[derive(Debug, Clone)]
+pub struct BatchSettings {
+    /// column of the values to convert
+    pub amount_column: String,
+    /// origin currency
+    pub from: BatchFrom,
+    /// destination currency
+    pub to: String,
+    /// delimiter of input and output (e.g. b',' for csv, b'\t' for tsv)
+    pub delimiter: u8,
+    /// rounding of converted amounts
+    pub rounding: Rounding,
+}
+
+/// Summary of a batch conversion
+#[derive(Debug, PartialEq, Default)]
+pub struct BatchReport {
+    /// number of rows converted
+    pub converted: usize,
+    /// number of rows in error (written with an error message)
+    pub errors: usize,
+}
+
+/// Columns added to the input columns
+const OUTPUT_COLUMNS: [&str; 3] = ["converted_amount", "conversion_rate", "error"];
+
+/// Convert all rows read from `reader` and write them in `writer` with the converted amount, the rate used and the error if any.
+/// A row in error doesn't stop the conversion of the others rows.
+pub fn convert_batch<R, W>(
+    rate_table: &RateTable,
+    settings: &BatchSettings,
+    reader: R,
+    writer: W,
+) -> Result<BatchReport>
+where
+    R: Read,
+    W: Write,
+{
+    let mut csv_rdr = csv::ReaderBuilder::new()
+        .delimiter(settings.delimiter)
+        .flexible(true)
+        .from_reader(reader);
+    let mut csv_wrt = csv::WriterBuilder::new()
+        .delimiter(settings.delimiter)
+        .flexible(true)
+        .from_writer(writer);
+
+    let headers = csv_rdr.headers()?.clone();
+    let amount_index = get_column_index(&headers, &settings.amount_column)?;
+    let from_index = match &settings.from {
+        BatchFrom::Column(column) => Some(get_column_index(&headers, column)?),
+        BatchFrom::Currency(_) => None,
+    };
+
+    csv_wrt.write_record(headers.iter().chain(OUTPUT_COLUMNS))?;
+
+    let mut report = BatchReport::default();
+
+    for (line, record) in csv_rdr.records().enumerate() {
+        // unreadable row is written empty with its error, reading stops on io errors (they would repeat)
+        let (record, res) = match record {
+            Ok(record) => {
+                let from = match (&settings.from, from_index) {
+                    (_, Some(index)) => record.get(index),
+                    (BatchFrom::Currency(currency), None) => Some(currency.as_str()),
+                    // from_index is always defined for BatchFrom::Column
+                    (BatchFrom::Column(_), None) => unreachable!(),
+                };
+
+                let res = convert_record(
+                    rate_table,
+                    &settings.rounding,
+                    record.get(amount_index),
+                    from,
+                    &settings.to,
+                );
+                (record.iter().map(str::to_string).collect(), res)
+            }
+            Err(e) if matches!(e.kind(), csv::ErrorKind::Io(_)) => return Err(e.into()),
+            Err(e) => (Vec::new(), Err(Error::Parse(e.to_string()))),
+        };
+
+        let added_columns = match res {
+            Ok((converted_amount, rate)) => {
+                report.converted += 1;
+                [
+                    converted_amount.to_string(),
+                    rate.to_string(),
+                    String::new(),
+                ]
+            }
+            Err(e) => {
+                report.errors += 1;
+                // line 1 is the header
+                tracing::warn!("line {} : {e}", line + 2);
+                [String::new(), String::new(), e.to_string()]
+            }
+        };
+
+        // short rows are padded so added columns stay under their headers
+        let mut fields = record;
+        if fields.len() < headers.len() {
+            fields.resize(headers.len(), String::new());
+        }
+
+        csv_wrt.write_record(fields.iter().chain(added_columns.iter()))?;
+    }
+
+    csv_wrt.flush()?;
+
+    Ok(report)
+}
+
+/// Convert the amount of a row and return the converted amount and the rate used
+fn convert_record(
+    rate_table: &RateTable,
+    rounding: &Rounding,
+    amount: Option<&str>,
+    from: Option<&str>,
+    to: &str,
+) -> Result<(Decimal, Decimal)> {
+    let amount = amount.ok_or(Error::Parse("missing amount".to_string()))?;
+    let amount = Decimal::from_str(amount.trim())
+        .map_err(|e| Error::Parse(format!("invalid amount {amount} : {e}")))?;
+    let from = from.ok_or(Error::Parse("missing origin currency".to_string()))?;
+
+    let rate = rate_table.resolve(from.trim(), to)?.rate;
+
+    Ok((rounding.round(amount * rate.rate, to), rate.rate))
+}
+
+/// Get index of `column` in `headers`
+fn get_column_index(headers: &csv::StringRecord, column: &str) -> Result<usize> {
+    headers
+        .iter()
+        .position(|header| header == column)
+        .ok_or(Error::Parse(format!(
+            "Column \"{column}\" not found in input !"
+        )))
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        common::{conversion_rate::ConversionRate, rate_table::RateTable},
+        error::Error,
+    };
+
+    use super::{BatchFrom, BatchReport, BatchSettings, Rounding};
+
+    fn rate_table() -> RateTable {
+        let conversion_rates = vec![
+            ConversionRate {
+                from: "EUR".to_string(),
+                to: "USD".to_string(),
+                rate: dec!(1.08),
+            },
+            ConversionRate {
+                from: "EUR".to_string(),
+                to: "TBH".to_string(),
+                rate: dec!(32),
+            },
+        ];
+
+        RateTable::build("EUR", &conversion_rates)
+    }
+
+    #[test]
+    fn convert_batch_from_column() {
+        let input = "label,amount,currency\nhotel,100,TBH\ntaxi,10,USD\nlunch,abc,USD\nflight,50,XXX\ndinner\n";
+        let mut output = Vec::new();
+
+        let settings = BatchSettings {
+            amount_column: "amount".to_string(),
+            from: BatchFrom::Column("currency".to_string()),
+            to: "EUR".to_string(),
+            delimiter: b',',
+            rounding: Rounding::default(),
+        };
+
+        let res = super::convert_batch(&rate_table(), &settings, input.as_bytes(), &mut output);
+
+        assert!(res.is_ok());
+        assert_eq!(
+            res.unwrap(),
+            BatchReport {
+                converted: 2,
+                errors: 3
+            }
+        );
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "label,amount,currency,converted_amount,conversion_rate,error"
+        );
+        // rounded half to even to EUR minor units
+        assert_eq!(lines[1], "hotel,100,TBH,3.12,0.03125,");
+        assert!(lines[3].starts_with("lunch,abc,USD,,,invalid amount"));
+        assert!(lines[4].starts_with("flight,50,XXX,,,"));
+        // short row is padded to the headers
+        assert_eq!(lines[5], "dinner,,,,,missing amount");
+    }
+
+    #[test]
+    fn convert_batch_unreadable_row() {
+        let mut input = b"amount\n10\n".to_vec();
+        input.extend_from_slice(b"\xff\n20\n");
+        let mut output = Vec::new();
+
+        let settings = BatchSettings {
+            amount_column: "amount".to_string(),
+            from: BatchFrom::Currency("EUR".to_string()),
+            to: "USD".to_string(),
+            delimiter: b',',
+            rounding: Rounding::default(),
+        };
+
+        let res = super::convert_batch(&rate_table(), &settings, input.as_slice(), &mut output);
+
+        // invalid row doesn't stop the others
+        assert_eq!(
+            res.unwrap(),
+            BatchReport {
+                converted: 2,
+                errors: 1
+            }
+        );
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[1], "10,10.80,1.08,");
+        assert!(lines[2].starts_with(",,,"));
+        assert!(lines[2].contains("UTF-8"));
+        assert_eq!(lines[3], "20,21.60,1.08,");
+    }
+
+    /// Reader failing on every read
+    struct FailingReader;
+
+    impl std::io::Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "broken pipe",
+            ))
+        }
+    }
+
+    #[test]
+    fn convert_batch_read_error() {
+        let input = std::io::Read::chain(b"amount\n10\n".as_slice(), FailingReader);
+        let mut output = Vec::new();
+
+        let settings = BatchSettings {
+            amount_column: "amount".to_string(),
+            from: BatchFrom::Currency("EUR".to_string()),
+            to: "USD".to_string(),
+            delimiter: b',',
+            rounding: Rounding::default(),
+        };
+
+        let res = super::convert_batch(&rate_table(), &settings, input, &mut output);
+
+        // reading stops at the first io error
+        assert!(matches!(res, Err(Error::StorageFile(_))));
+    }
+
+    #[test]
+    fn convert_batch_from_currency() {
+        let input = "amount\n10\n";
+        let mut output = Vec::new();
+
+        let settings = BatchSettings {
+            amount_column: "amount".to_string(),
+            from: BatchFrom::Currency("EUR".to_string()),
+            to: "USD".to_string(),
+            delimiter: b'\t',
+            rounding: Rounding::default(),
+        };
+
+        let res = super::convert_batch(&rate_table(), &settings, input.as_bytes(), &mut output);
+
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap().converted, 1);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "amount\tconverted_amount\tconversion_rate\terror\n10\t10.80\t1.08\t\n"
+        );
+    }
+
+    #[test]
+    fn convert_batch_missing_column() {
+        let input = "value,currency\n10,USD\n";
+        let mut output = Vec::new();
+
+        let settings = BatchSettings {
+            amount_column: "amount".to_string(),
+            from: BatchFrom::Column("currency".to_string()),
+            to: "EUR".to_string(),
+            delimiter: b',',
+            rounding: Rounding::default(),
+        };
+
+        let res = super::convert_batch(&rate_table(), &settings, input.as_bytes(), &mut output);
+
+        assert!(res.is_err());
+    }
+}
@@ -1,8 +1,76 @@
+use std::time::Duration;
+
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::{conversion_rate::ConversionRate, rate_table::RateTable},
+    error::{Error, Result},
+    storage::common::{HistoryStorageManager, Snapshot, StaleDataError, StorageManager},
+    update::{provider::RateProvider, update_converison_rates::update_conversion_rates},
+};
+
+/// What to do when conversion rates are older than the maximum age allowed
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum StaleRatesPolicy {
+    /// convert and warn
+    #[default]
+    Warn,
+    /// fail with [`Error::StaleData`]
+    Error,
+    /// update conversion rates then convert
+    AutoUpdate,
+}
+
+/// Freshness of conversion rates after [`check_conversion_rates_age`]
+#[derive(Debug, PartialEq)]
+pub enum RatesFreshness {
+    Fresh,
+    /// older than the maximum age (with [`StaleRatesPolicy::Warn`])
+    Stale(StaleDataError),
+    /// updated because they were older than the maximum age (with [`StaleRatesPolicy::AutoUpdate`])
+    Updated,
+}
+
+/// Check conversion rates aren't older than `max_age` and apply `policy` if they are
+pub async fn check_conversion_rates_age<P, T>(
+    conversion_rates_storage_manager: &T,
+    max_age: Duration,
+    policy: StaleRatesPolicy,
+    provider: &P,
+    base: &str,
+) -> Result<RatesFreshness>
+where
+    P: RateProvider,
+    T: HistoryStorageManager<ConversionRate>,
+{
+    let data_info =
+        StorageManager::<ConversionRate>::get_data_info(conversion_rates_storage_manager).await?;
+
+    let Err(stale) = data_info.check_age(max_age) else {
+        return Ok(RatesFreshness::Fresh);
+    };
 
-use crate::{common::conversion_rate::ConversionRate, storage::common::StorageManager};
+    match policy {
+        StaleRatesPolicy::Warn => Ok(RatesFreshness::Stale(stale)),
+        StaleRatesPolicy::Error => Err(stale.into()),
+        StaleRatesPolicy::AutoUpdate => {
+            tracing::info!("{stale}, update conversion rates");
+            update_conversion_rates(provider, base, conversion_rates_storage_manager).await?;
+            Ok(RatesFreshness::Updated)
+        }
+    }
+}
 
-use anyhow::Result;
+/// Result of a conversion
+#[derive(Debug, PartialEq)]
+pub struct Conversion {
+    pub value: Decimal,
+    /// currencies of the conversion rates path used (e.g. ["USD", "EUR", "JPY"])
+    pub path: Vec<String>,
+}
 
 /// Convert a `value` `from` a currency `to` another
 pub async fn convert<T>(
@@ -11,28 +79,134 @@ pub async fn convert<T>(
     from: &str,
     to: &str,
     value: Decimal,
-) -> Result<Decimal>
+) -> Result<Conversion>
 where
     T: StorageManager<ConversionRate>,
 {
-    let conversion_rates = conversion_rates_storage_manager.get_all().await?;
+    let rate_table = RateTable::from_storage(conversion_rates_storage_manager, base).await?;
+
+    convert_with_table(&rate_table, from, to, value)
+}
 
-    let rate = ConversionRate::get_conversion_rate(base, &conversion_rates, from, to)?;
+/// Convert a `value` `from` a currency `to` another with conversion rates of `rate_table`
+pub fn convert_with_table(
+    rate_table: &RateTable,
+    from: &str,
+    to: &str,
+    value: Decimal,
+) -> Result<Conversion> {
+    let resolved = rate_table.resolve(from, to)?;
 
-    Ok(value * rate.rate)
+    Ok(Conversion {
+        value: value * resolved.rate.rate,
+        path: resolved.path,
+    })
+}
+
+/// Result of a conversion using conversion rates history
+#[derive(Debug, PartialEq)]
+pub struct DatedConversion {
+    pub value: Decimal,
+    /// Effective date of the conversion rates used
+    pub date: NaiveDate,
+    /// currencies of the conversion rates path used
+    pub path: Vec<String>,
+}
+
+/// Convert a `value` `from` a currency `to` another with conversion rates as of `date` (the nearest earlier stored snapshot is used)
+pub async fn convert_at_date<T>(
+    conversion_rates_storage_manager: &T,
+    base: &str,
+    from: &str,
+    to: &str,
+    value: Decimal,
+    date: NaiveDate,
+) -> Result<DatedConversion>
+where
+    T: HistoryStorageManager<ConversionRate>,
+{
+    let snapshot = get_conversion_rates_at_date(conversion_rates_storage_manager, date).await?;
+
+    let conversion = convert_with_table(&RateTable::build(base, &snapshot.data), from, to, value)?;
+
+    Ok(DatedConversion {
+        value: conversion.value,
+        date: snapshot.date,
+        path: conversion.path,
+    })
+}
+
+/// Get stored conversion rates as of `date` (the nearest earlier stored snapshot is used)
+pub async fn get_conversion_rates_at_date<T>(
+    conversion_rates_storage_manager: &T,
+    date: NaiveDate,
+) -> Result<Snapshot<ConversionRate>>
+where
+    T: HistoryStorageManager<ConversionRate>,
+{
+    let snapshot = conversion_rates_storage_manager
+        .get_snapshot(date)
+        .await?
+        .ok_or(Error::MissingConversionRates(date))?;
+
+    if snapshot.date != date {
+        tracing::warn!(
+            "No conversion rates stored for {date}, conversion rates of {} are used",
+            snapshot.date
+        );
+    }
+
+    Ok(snapshot)
+}
+
+/// Result of the conversion to one of many currencies
+#[derive(Debug)]
+pub struct TargetConversion {
+    /// destination currency
+    pub to: String,
+    pub value: Result<Conversion>,
+}
+
+/// Convert a `value` `from` a currency to each currency of `to` (with conversion rates of `rate_table`)
+/// A currency without conversion rate doesn't stop the conversion to the others currencies.
+pub fn convert_to_many(
+    rate_table: &RateTable,
+    from: &str,
+    to: &[String],
+    value: Decimal,
+) -> Vec<TargetConversion> {
+    to.iter()
+        .map(|to| TargetConversion {
+            to: to.clone(),
+            value: convert_with_table(rate_table, from, to, value),
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod test {
-    use std::path::PathBuf;
+    use std::{
+        path::PathBuf,
+        time::{Duration, SystemTime},
+    };
 
+    use chrono::{NaiveDate, Utc};
+    use httpmock::{Method::GET, MockServer};
     use rust_decimal_macros::dec;
+    use serde_json::json;
 
     use crate::{
-        common::conversion_rate::ConversionRate,
-        storage::{common::StorageManager, tsv::TSVStorageManager},
+        common::{conversion_rate::ConversionRate, rate_table::RateTable},
+        error::Error,
+        storage::{
+            common::{HistoryStorageManager, Snapshot, StorageManager},
+            tsv::TSVStorageManager,
+        },
+        update::exchange_rates_api::ExchangeRatesApiProvider,
     };
 
+    use super::{RatesFreshness, StaleRatesPolicy};
+
     async fn setup(dirpath: String, data: Vec<ConversionRate>) -> TSVStorageManager {
         std::fs::create_dir_all(&dirpath).unwrap();
 
@@ -62,6 +236,215 @@ mod test {
 
         let res = super::convert(&storage_manager, "EUR", &from, &to, dec!(10.0)).await;
         assert!(res.is_ok());
-        assert_eq!(res.unwrap(), dec!(10.8));
+        assert_eq!(
+            res.unwrap(),
+            super::Conversion {
+                value: dec!(10.8),
+                path: vec![from, to]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn convert_at_date() {
+        let dirpath = "./temp/test/convert/convert_currency_at_date/";
+        let from = "EUR".to_string();
+        let to = "USD".to_string();
+        std::fs::create_dir_all(dirpath).unwrap();
+        let storage_manager =
+            TSVStorageManager::build(PathBuf::from(dirpath.to_string() + "conversion_rate.tsv"));
+
+        storage_manager
+            .update_snapshot(&Snapshot {
+                date: NaiveDate::from_ymd_opt(2024, 9, 2).unwrap(),
+                data: vec![ConversionRate {
+                    from: from.clone(),
+                    to: to.clone(),
+                    rate: dec!(1.07),
+                }],
+            })
+            .await
+            .unwrap();
+
+        // exact date
+        let date = NaiveDate::from_ymd_opt(2024, 9, 2).unwrap();
+        let res =
+            super::convert_at_date(&storage_manager, "EUR", &from, &to, dec!(10.0), date).await;
+        assert!(res.is_ok());
+        assert_eq!(
+            res.unwrap(),
+            super::DatedConversion {
+                value: dec!(10.7),
+                date,
+                path: vec![from.clone(), to.clone()]
+            }
+        );
+
+        // nearest earlier snapshot
+        let res = super::convert_at_date(
+            &storage_manager,
+            "EUR",
+            &from,
+            &to,
+            dec!(10.0),
+            NaiveDate::from_ymd_opt(2024, 9, 4).unwrap(),
+        )
+        .await;
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap().date, date);
+
+        // no snapshot before
+        let res = super::convert_at_date(
+            &storage_manager,
+            "EUR",
+            &from,
+            &to,
+            dec!(10.0),
+            NaiveDate::from_ymd_opt(2024, 9, 1).unwrap(),
+        )
+        .await;
+        assert!(res.is_err());
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+
+    #[test]
+    fn convert_to_many() {
+        let data = vec![
+            ConversionRate {
+                from: "EUR".to_string(),
+                to: "USD".to_string(),
+                rate: dec!(1.08),
+            },
+            ConversionRate {
+                from: "EUR".to_string(),
+                to: "GBP".to_string(),
+                rate: dec!(0.84),
+            },
+        ];
+        let to = vec!["USD".to_string(), "XXX".to_string(), "GBP".to_string()];
+
+        let res = super::convert_to_many(&RateTable::build("EUR", &data), "EUR", &to, dec!(100));
+
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[0].to, "USD");
+        assert_eq!(res[0].value.as_ref().unwrap().value, dec!(108));
+        assert!(res[1].value.is_err());
+        assert_eq!(res[2].to, "GBP");
+        assert_eq!(res[2].value.as_ref().unwrap().value, dec!(84));
+    }
+
+    /// Build a storage with conversion rates updated 3 days ago
+    async fn setup_stale(dirpath: &str) -> TSVStorageManager {
+        let storage_manager = setup(
+            dirpath.to_string(),
+            vec![ConversionRate {
+                from: "EUR".to_string(),
+                to: "USD".to_string(),
+                rate: dec!(1.08),
+            }],
+        )
+        .await;
+
+        std::fs::File::options()
+            .write(true)
+            .open(dirpath.to_string() + "conversion_rate.tsv")
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(3 * 24 * 3600))
+            .unwrap();
+
+        storage_manager
+    }
+
+    #[tokio::test]
+    async fn check_conversion_rates_age() {
+        let dirpath = "./temp/test/convert/check_conversion_rates_age/";
+        let storage_manager = setup_stale(dirpath).await;
+        // never called
+        let provider = ExchangeRatesApiProvider::build("123", "", "", "");
+
+        let res = super::check_conversion_rates_age(
+            &storage_manager,
+            Duration::from_secs(4 * 24 * 3600),
+            StaleRatesPolicy::Error,
+            &provider,
+            "EUR",
+        )
+        .await;
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), RatesFreshness::Fresh);
+
+        let res = super::check_conversion_rates_age(
+            &storage_manager,
+            Duration::from_secs(24 * 3600),
+            StaleRatesPolicy::Warn,
+            &provider,
+            "EUR",
+        )
+        .await;
+        assert!(res.is_ok());
+        assert!(matches!(res.unwrap(), RatesFreshness::Stale(_)));
+
+        let res = super::check_conversion_rates_age(
+            &storage_manager,
+            Duration::from_secs(24 * 3600),
+            StaleRatesPolicy::Error,
+            &provider,
+            "EUR",
+        )
+        .await;
+        assert!(res.is_err());
+        assert!(matches!(res.unwrap_err(), Error::StaleData(_)));
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+
+    #[tokio::test]
+    async fn check_conversion_rates_age_auto_update() {
+        let dirpath = "./temp/test/convert/check_conversion_rates_age_auto_update/";
+        let storage_manager = setup_stale(dirpath).await;
+
+        // latest conversion rates are effective today
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/latest");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "success": true,
+                    "timestamp": 1519296206,
+                    "base": "EUR",
+                    "date": Utc::now().date_naive(),
+                    "rates": {
+                        "USD": 1.09,
+                    }
+                }));
+        });
+
+        let provider = ExchangeRatesApiProvider::build(
+            "123",
+            &server.url("/symbols?access_key={api_key}"),
+            &server.url("/latest?access_key={api_key}&base={base}"),
+            &server.url("/{date}?access_key={api_key}&base={base}"),
+        );
+
+        let res = super::check_conversion_rates_age(
+            &storage_manager,
+            Duration::from_secs(24 * 3600),
+            StaleRatesPolicy::AutoUpdate,
+            &provider,
+            "EUR",
+        )
+        .await;
+
+        mock.assert();
+
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), RatesFreshness::Updated);
+
+        let res = super::convert(&storage_manager, "EUR", "EUR", "USD", dec!(10)).await;
+        assert_eq!(res.unwrap().value, dec!(10.9));
+
+        std::fs::remove_dir_all(dirpath).unwrap();
     }
 }
@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::{common::iso4217::minor_units, error::Error};
+
+/// How converted values are rounded
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoundingMode {
+    /// round half to even (banker's rounding)
+    #[default]
+    HalfEven,
+    /// round half away from zero
+    HalfUp,
+    /// drop extra decimals
+    Truncate,
+    /// keep all decimals
+    None,
+}
+
+impl FromStr for RoundingMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "half-even" => RoundingMode::HalfEven,
+            "half-up" => RoundingMode::HalfUp,
+            "truncate" => RoundingMode::Truncate,
+            "none" => RoundingMode::None,
+            _ => {
+                return Err(Error::Parse(format!(
+                    "\"{s}\" isn't a rounding mode (half-even, half-up, truncate or none)"
+                )))
+            }
+        })
+    }
+}
+
+/// Rounding of converted values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rounding {
+    pub mode: RoundingMode,
+    /// number of decimals (default : minor units of the destination currency, values of unknown currencies aren't rounded)
+    pub precision: Option<u32>,
+}
+
+impl Rounding {
+    /// Round `value` of `currency`, decimals are padded up to the precision (e.g. "10.80")
+    pub fn round(&self, value: Decimal, currency: &str) -> Decimal {
+        let strategy = match self.mode {
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::Truncate => RoundingStrategy::ToZero,
+            RoundingMode::None => return value,
+        };
+
+        let Some(precision) = self.precision.or(minor_units(currency)) else {
+            return value;
+        };
+
+        let mut res = value.round_dp_with_strategy(precision, strategy);
+        res.rescale(precision);
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal_macros::dec;
+
+    use super::{Rounding, RoundingMode};
+
+    #[test]
+    fn round() {
+        let rounding = Rounding::default();
+
+        assert_eq!(
+            rounding.round(dec!(10.80000000), "USD").to_string(),
+            "10.80"
+        );
+        assert_eq!(rounding.round(dec!(10.8), "USD").to_string(), "10.80");
+        assert_eq!(rounding.round(dec!(1.125), "EUR").to_string(), "1.12");
+        assert_eq!(rounding.round(dec!(1620.5), "JPY").to_string(), "1620");
+        assert_eq!(rounding.round(dec!(1.23456), "KWD").to_string(), "1.235");
+        // unknown currency
+        assert_eq!(rounding.round(dec!(0.123456789), "BTC"), dec!(0.123456789));
+
+        let rounding = Rounding {
+            mode: RoundingMode::HalfUp,
+            precision: None,
+        };
+        assert_eq!(rounding.round(dec!(1.125), "EUR").to_string(), "1.13");
+
+        let rounding = Rounding {
+            mode: RoundingMode::Truncate,
+            precision: Some(1),
+        };
+        assert_eq!(rounding.round(dec!(1.19), "EUR").to_string(), "1.1");
+        assert_eq!(rounding.round(dec!(-1.19), "EUR").to_string(), "-1.1");
+
+        let rounding = Rounding {
+            mode: RoundingMode::None,
+            precision: Some(1),
+        };
+        assert_eq!(rounding.round(dec!(1.19), "EUR"), dec!(1.19));
+    }
+
+    #[test]
+    fn rounding_mode_from_str() {
+        assert_eq!(
+            "half-up".parse::<RoundingMode>().unwrap(),
+            RoundingMode::HalfUp
+        );
+        assert!("up".parse::<RoundingMode>().is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use chrono::NaiveDate;
+
+use crate::storage::common::StaleDataError;
+
+/// Errors returned by the library
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// currency isn't in conversion rates (or in a provider feed)
+    #[error("{0} symbols not found !")]
+    UnknownCurrency(String),
+
+    /// conversion rates don't link both currencies
+    #[error("No conversion path from {from} to {to} !")]
+    NoConversionPath { from: String, to: String },
+
+    /// conversion rate can't be computed (zero or overflowing rates)
+    #[error("Invalid conversion rate from {from} to {to} !")]
+    InvalidConversionRate { from: String, to: String },
+
+    /// no conversion rates stored for a date
+    #[error("No conversion rates stored at or before {0} !")]
+    MissingConversionRates(NaiveDate),
+
+    /// provider can't be reached or its response can't be read
+    #[error("Provider request failed : {0}")]
+    ProviderHttp(#[from] reqwest::Error),
+
+    /// provider answered with an error
+    #[error("Call {url} failed : {code} - {message}")]
+    ProviderApi {
+        url: String,
+        code: String,
+        message: String,
+    },
+
+    /// provider can't serve the request (unsupported or empty data)
+    #[error("{0}")]
+    Provider(String),
+
+    /// storage file can't be read or written
+    #[error("Storage I/O error : {0}")]
+    StorageIo(#[from] std::io::Error),
+
+    /// storage file content is invalid
+    #[error("Storage file error : {0}")]
+    StorageFile(#[from] csv::Error),
+
+    /// database query or connection failed
+    #[error("Database error : {0}")]
+    Database(#[from] sqlx::Error),
+
+    /// value can't be parsed (amounts, dates, provider feeds, ...)
+    #[error("{0}")]
+    Parse(String),
+
+    /// stored data are older than the maximum age allowed
+    #[error(transparent)]
+    StaleData(#[from] StaleDataError),
+
+    /// data read back from the destination of a copy don't match the source
+    #[error("Copy check failed : {expected} items expected but {actual} stored")]
+    CopyMismatch { expected: usize, actual: usize },
+
+    /// settings can't be used (invalid url, unresolvable secret, ...)
+    #[error("{0}")]
+    InvalidSettings(String),
+}
+
+/// Result with library [`Error`]
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl From<sqlx::migrate::MigrateError> for Error {
+    fn from(error: sqlx::migrate::MigrateError) -> Self {
+        Error::Database(sqlx::Error::Migrate(Box::new(error)))
+    }
+}
+
+impl From<rust_decimal::Error> for Error {
+    fn from(error: rust_decimal::Error) -> Self {
+        Error::Parse(error.to_string())
+    }
+}
+
+impl From<chrono::ParseError> for Error {
+    fn from(error: chrono::ParseError) -> Self {
+        Error::Parse(error.to_string())
+    }
+}
+
+impl From<quick_xml::Error> for Error {
+    fn from(error: quick_xml::Error) -> Self {
+        Error::Parse(error.to_string())
+    }
+}
+
+impl From<quick_xml::events::attributes::AttrError> for Error {
+    fn from(error: quick_xml::events::attributes::AttrError) -> Self {
+        Error::Parse(error.to_string())
+    }
+}
@@ -1,5 +1,6 @@
 pub mod common;
 pub mod convert;
+pub mod error;
 pub mod list;
 pub mod storage;
 pub mod update;
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use crate::error::Result;
 
 pub trait ListDataItem {
     /// Define how write item in a list
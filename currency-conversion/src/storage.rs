@@ -1,3 +1,6 @@
 pub mod common;
-pub mod tsv;
+pub mod copy;
+pub mod factory;
 pub mod psql;
+pub mod sqlite;
+pub mod tsv;
@@ -1,39 +1,93 @@
 use std::{future::Future, time::Duration};
 
-use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use crate::error::Result;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize, Serializer};
 
-use super::{psql::PSQLStorageSettings, tsv::TSVStorageSettings};
-use sqlx::postgres::types::PgInterval;
-use sqlx::Row;
+use super::{psql::PSQLStorageSettings, sqlite::SQLiteStorageSettings, tsv::TSVStorageSettings};
 
 /// Storage type available
+// Only built once from the configuration, so the variant size does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Deserialize, Serialize, Eq, PartialEq, Clone)]
 pub enum StorageType {
     TSV(TSVStorageSettings),
     PSQL(PSQLStorageSettings),
+    SQLITE(SQLiteStorageSettings),
 }
 
 /// Information about data
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
 pub enum DataInfo {
     Success(DataInfoSuccess),
     Error(DataInfoError),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct DataInfoSuccess {
+    /// serialized as a number of seconds
+    #[serde(serialize_with = "serialize_duration_as_seconds")]
     pub seconds_since_last_update: Duration,
     pub number_of_line: usize,
 }
 
-
-
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct DataInfoError {
+    /// serialized as its message
+    #[serde(serialize_with = "serialize_error_as_string")]
     pub error: anyhow::Error,
 }
 
+impl DataInfo {
+    /// Check data aren't older than `max_age` (data in error aren't checked)
+    pub fn check_age(&self, max_age: Duration) -> Result<(), StaleDataError> {
+        match self {
+            DataInfo::Success(info) if info.seconds_since_last_update > max_age => {
+                Err(StaleDataError {
+                    age: info.seconds_since_last_update,
+                    max_age,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Data older than the maximum age allowed
+#[derive(Debug, PartialEq)]
+pub struct StaleDataError {
+    pub age: Duration,
+    pub max_age: Duration,
+}
+
+impl std::fmt::Display for StaleDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Data are stale : last update {} ago (maximum age : {})",
+            humantime::format_duration(Duration::from_secs(self.age.as_secs())),
+            humantime::format_duration(self.max_age)
+        )
+    }
+}
+
+impl std::error::Error for StaleDataError {}
+
+fn serialize_duration_as_seconds<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_secs())
+}
+
+fn serialize_error_as_string<S: Serializer>(
+    error: &anyhow::Error,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{error:#}"))
+}
+
 /// Interface to store and retrieve data from storage
 pub trait StorageManager<T>
 where
@@ -48,3 +102,86 @@ where
     /// Get informations about data (last update, number, ...)
     fn get_data_info(&self) -> impl Future<Output = Result<DataInfo>>;
 }
+
+/// Data effective at a date
+#[derive(Debug, PartialEq, Clone)]
+pub struct Snapshot<T> {
+    pub date: NaiveDate,
+    pub data: Vec<T>,
+}
+
+/// Interface to store and retrieve history of data from storage
+pub trait HistoryStorageManager<T>: StorageManager<T>
+where
+    T: Serialize + for<'de> Deserialize<'de>,
+{
+    /// Add (or replace) data effective at `snapshot.date` without deleting other dates
+    fn update_snapshot(&self, snapshot: &Snapshot<T>) -> impl Future<Output = Result<()>>;
+
+    /// Get data as of `date` (the nearest snapshot effective at or before `date`)
+    fn get_snapshot(&self, date: NaiveDate) -> impl Future<Output = Result<Option<Snapshot<T>>>>;
+
+    /// Get dates of all stored snapshots (sorted)
+    fn get_snapshot_dates(&self) -> impl Future<Output = Result<Vec<NaiveDate>>>;
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use anyhow::anyhow;
+    use serde_json::json;
+
+    use super::{DataInfo, DataInfoError, DataInfoSuccess, StaleDataError};
+
+    #[test]
+    fn serialize_data_info() {
+        let success = DataInfo::Success(DataInfoSuccess {
+            seconds_since_last_update: Duration::from_millis(3_600_500),
+            number_of_line: 170,
+        });
+
+        assert_eq!(
+            serde_json::to_value(success).unwrap(),
+            json!({"status": "success", "seconds_since_last_update": 3600, "number_of_line": 170})
+        );
+
+        let error = DataInfo::Error(DataInfoError {
+            error: anyhow!("file not found"),
+        });
+
+        assert_eq!(
+            serde_json::to_value(error).unwrap(),
+            json!({"status": "error", "error": "file not found"})
+        );
+    }
+
+    #[test]
+    fn check_age() {
+        let info = DataInfo::Success(DataInfoSuccess {
+            seconds_since_last_update: Duration::from_secs(3 * 24 * 3600),
+            number_of_line: 170,
+        });
+
+        assert!(info.check_age(Duration::from_secs(4 * 24 * 3600)).is_ok());
+
+        let res = info.check_age(Duration::from_secs(2 * 24 * 3600));
+        assert_eq!(
+            res,
+            Err(StaleDataError {
+                age: Duration::from_secs(3 * 24 * 3600),
+                max_age: Duration::from_secs(2 * 24 * 3600),
+            })
+        );
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Data are stale : last update 3days ago (maximum age : 2days)"
+        );
+
+        let error = DataInfo::Error(DataInfoError {
+            error: anyhow!("file not found"),
+        });
+
+        assert!(error.check_age(Duration::ZERO).is_ok());
+    }
+}
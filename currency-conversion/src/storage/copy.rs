@@ -0,0 +1,281 @@
+//! Copy of stored data from a storage to an other (no provider call)
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+use super::common::{HistoryStorageManager, Snapshot, StorageManager};
+
+/// Number of snapshots and items copied (or to copy on dry run)
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct CopyCount {
+    pub snapshots: usize,
+    pub items: usize,
+}
+
+/// Copy all data of `source` to `destination` then check `destination` stores as many items (nothing is written on `dry_run`)
+pub async fn copy_data<T>(
+    source: &impl StorageManager<T>,
+    destination: &impl StorageManager<T>,
+    dry_run: bool,
+) -> Result<CopyCount>
+where
+    T: Serialize + for<'de> Deserialize<'de>,
+{
+    let data = source.get_all().await?;
+    let count = CopyCount {
+        snapshots: 1,
+        items: data.len(),
+    };
+
+    if dry_run {
+        return Ok(count);
+    }
+
+    destination.update(&data).await?;
+
+    let stored = destination.get_all().await?;
+    check_count(count.items, stored.len())?;
+
+    Ok(count)
+}
+
+/// Copy all snapshots of `source` to `destination` (oldest first, so the latest stays the current one) then check each snapshot of `destination` stores as many items
+///
+/// Data without history (e.g. stored before history was kept) are copied as a single snapshot with [`copy_data`]
+pub async fn copy_history<T>(
+    source: &impl HistoryStorageManager<T>,
+    destination: &impl HistoryStorageManager<T>,
+    dry_run: bool,
+) -> Result<CopyCount>
+where
+    T: Serialize + for<'de> Deserialize<'de>,
+{
+    let dates = source.get_snapshot_dates().await?;
+    if dates.is_empty() {
+        return copy_data(source, destination, dry_run).await;
+    }
+
+    let mut snapshots = Vec::new();
+    for date in dates {
+        if let Some(snapshot) = source.get_snapshot(date).await? {
+            snapshots.push(snapshot);
+        }
+    }
+
+    let count = CopyCount {
+        snapshots: snapshots.len(),
+        items: snapshots.iter().map(|snapshot| snapshot.data.len()).sum(),
+    };
+
+    if dry_run {
+        return Ok(count);
+    }
+
+    for snapshot in &snapshots {
+        destination.update_snapshot(snapshot).await?;
+    }
+
+    for Snapshot { date, data } in &snapshots {
+        let stored = destination
+            .get_snapshot(*date)
+            .await?
+            .filter(|stored| stored.date == *date)
+            .map_or(0, |stored| stored.data.len());
+        check_count(data.len(), stored)?;
+    }
+
+    Ok(count)
+}
+
+/// Check items stored by the destination match items read from the source
+fn check_count(expected: usize, actual: usize) -> Result<()> {
+    match expected == actual {
+        true => Ok(()),
+        false => Err(Error::CopyMismatch { expected, actual }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+        error::Error,
+        storage::{
+            common::{HistoryStorageManager, Snapshot, StorageManager},
+            sqlite::{SQLiteStorageManager, SQLiteStorageSettings},
+            tsv::TSVStorageManager,
+        },
+    };
+
+    use super::{check_count, copy_data, copy_history, CopyCount};
+
+    #[tokio::test]
+    async fn copy_symbols() {
+        let dirpath = "./temp/test/storage/copy/copy_symbols/";
+        std::fs::create_dir_all(dirpath).unwrap();
+
+        let source = TSVStorageManager::build(PathBuf::from(dirpath.to_string() + "symbols.tsv"));
+        let destination = SQLiteStorageManager::from_settings(SQLiteStorageSettings {
+            file_path: PathBuf::from(dirpath.to_string() + "symbols.db"),
+        })
+        .await
+        .unwrap();
+
+        let symbols = vec![
+            Symbols {
+                code: "EUR".to_string(),
+                name: "Euro".to_string(),
+            },
+            Symbols {
+                code: "USD".to_string(),
+                name: "United States Dollar".to_string(),
+            },
+        ];
+        source.update(&symbols).await.unwrap();
+
+        // dry run writes nothing
+        let count = copy_data::<Symbols>(&source, &destination, true)
+            .await
+            .unwrap();
+        assert_eq!(
+            count,
+            CopyCount {
+                snapshots: 1,
+                items: 2
+            }
+        );
+        let res = StorageManager::<Symbols>::get_all(&destination)
+            .await
+            .unwrap();
+        assert!(res.is_empty());
+
+        let count = copy_data::<Symbols>(&source, &destination, false)
+            .await
+            .unwrap();
+        assert_eq!(count.items, 2);
+        let res = StorageManager::<Symbols>::get_all(&destination)
+            .await
+            .unwrap();
+        assert_eq!(res, symbols);
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+
+    #[tokio::test]
+    async fn copy_conversion_rates_history() {
+        let dirpath = "./temp/test/storage/copy/copy_conversion_rates_history/";
+        std::fs::create_dir_all(dirpath).unwrap();
+
+        let source =
+            TSVStorageManager::build(PathBuf::from(dirpath.to_string() + "conversion_rates.tsv"));
+        let destination = SQLiteStorageManager::from_settings(SQLiteStorageSettings {
+            file_path: PathBuf::from(dirpath.to_string() + "conversion_rates.db"),
+        })
+        .await
+        .unwrap();
+
+        let first = Snapshot {
+            date: NaiveDate::from_ymd_opt(2024, 9, 2).unwrap(),
+            data: vec![ConversionRate {
+                from: "EUR".to_string(),
+                to: "USD".to_string(),
+                rate: dec!(1.10),
+            }],
+        };
+        let second = Snapshot {
+            date: NaiveDate::from_ymd_opt(2024, 9, 5).unwrap(),
+            data: vec![
+                ConversionRate {
+                    from: "EUR".to_string(),
+                    to: "USD".to_string(),
+                    rate: dec!(1.11),
+                },
+                ConversionRate {
+                    from: "EUR".to_string(),
+                    to: "GBP".to_string(),
+                    rate: dec!(0.84),
+                },
+            ],
+        };
+        source.update_snapshot(&second).await.unwrap();
+        source.update_snapshot(&first).await.unwrap();
+
+        let count = copy_history::<ConversionRate>(&source, &destination, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            count,
+            CopyCount {
+                snapshots: 2,
+                items: 3
+            }
+        );
+
+        // snapshots keep their dates and the latest is the current one
+        assert_eq!(
+            destination.get_snapshot_dates().await.unwrap(),
+            vec![first.date, second.date]
+        );
+        assert_eq!(
+            destination.get_snapshot(first.date).await.unwrap(),
+            Some(first)
+        );
+        let res = StorageManager::<ConversionRate>::get_all(&destination)
+            .await
+            .unwrap();
+        assert_eq!(res, second.data);
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+
+    #[tokio::test]
+    async fn copy_history_without_snapshots() {
+        let dirpath = "./temp/test/storage/copy/copy_history_without_snapshots/";
+        std::fs::create_dir_all(dirpath).unwrap();
+
+        // file written before history was kept
+        let file_path = PathBuf::from(dirpath.to_string() + "conversion_rates.tsv");
+        std::fs::write(&file_path, "from\tto\trate\nEUR\tUSD\t1.10\n").unwrap();
+
+        let source = TSVStorageManager::build(file_path);
+        let destination = TSVStorageManager::build(PathBuf::from(
+            dirpath.to_string() + "copy/conversion_rates.tsv",
+        ));
+        std::fs::create_dir_all(dirpath.to_string() + "copy").unwrap();
+
+        let count = copy_history::<ConversionRate>(&source, &destination, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            count,
+            CopyCount {
+                snapshots: 1,
+                items: 1
+            }
+        );
+        let res = StorageManager::<ConversionRate>::get_all(&destination)
+            .await
+            .unwrap();
+        assert_eq!(res.len(), 1);
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+
+    #[test]
+    fn check_count_mismatch() {
+        assert!(check_count(3, 3).is_ok());
+        assert!(matches!(
+            check_count(3, 2),
+            Err(Error::CopyMismatch {
+                expected: 3,
+                actual: 2
+            })
+        ));
+    }
+}
@@ -0,0 +1,185 @@
+use crate::error::Result;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    common::{DataInfo, HistoryStorageManager, Snapshot, StorageManager, StorageType},
+    psql::PSQLStorageManager,
+    sqlite::SQLiteStorageManager,
+    tsv::TSVStorageManager,
+};
+
+/// Storage manager of any storage type (built from a [`StorageType`])
+pub enum AnyStorageManager {
+    TSV(TSVStorageManager),
+    PSQL(PSQLStorageManager),
+    SQLITE(SQLiteStorageManager),
+}
+
+/// Call the same expression on the storage manager whatever its type
+macro_rules! dispatch {
+    ($storage_manager:expr, $inner:ident => $call:expr) => {
+        match $storage_manager {
+            AnyStorageManager::TSV($inner) => $call,
+            AnyStorageManager::PSQL($inner) => $call,
+            AnyStorageManager::SQLITE($inner) => $call,
+        }
+    };
+}
+
+impl AnyStorageManager {
+    /// Build the storage manager of the storage type
+    pub async fn from_settings(settings: StorageType) -> Result<AnyStorageManager> {
+        Ok(match settings {
+            StorageType::TSV(settings) => {
+                AnyStorageManager::TSV(TSVStorageManager::from_settings(settings)?)
+            }
+            StorageType::PSQL(settings) => {
+                AnyStorageManager::PSQL(PSQLStorageManager::from_settings(settings).await?)
+            }
+            StorageType::SQLITE(settings) => {
+                AnyStorageManager::SQLITE(SQLiteStorageManager::from_settings(settings).await?)
+            }
+        })
+    }
+
+    /// Apply pending migrations of databases (nothing to do for files)
+    pub async fn migrate(&self) -> Result<()> {
+        match self {
+            AnyStorageManager::TSV(_) => Ok(()),
+            AnyStorageManager::PSQL(storage_manager) => storage_manager.migrate().await,
+            AnyStorageManager::SQLITE(storage_manager) => storage_manager.migrate().await,
+        }
+    }
+
+    /// Version of the last migration applied to databases (none for files)
+    pub async fn schema_version(&self) -> Result<Option<i64>> {
+        match self {
+            AnyStorageManager::TSV(_) => Ok(None),
+            AnyStorageManager::PSQL(storage_manager) => storage_manager.schema_version().await,
+            AnyStorageManager::SQLITE(storage_manager) => storage_manager.schema_version().await,
+        }
+    }
+}
+
+impl<T> StorageManager<T> for AnyStorageManager
+where
+    T: Serialize + for<'de> Deserialize<'de>,
+    TSVStorageManager: StorageManager<T>,
+    PSQLStorageManager: StorageManager<T>,
+    SQLiteStorageManager: StorageManager<T>,
+{
+    async fn update(&self, data: &[T]) -> Result<()> {
+        dispatch!(self, storage_manager => storage_manager.update(data).await)
+    }
+
+    async fn get_all(&self) -> Result<Vec<T>> {
+        dispatch!(self, storage_manager => storage_manager.get_all().await)
+    }
+
+    async fn get_data_info(&self) -> Result<DataInfo> {
+        dispatch!(self, storage_manager => StorageManager::<T>::get_data_info(storage_manager).await)
+    }
+}
+
+impl<T> HistoryStorageManager<T> for AnyStorageManager
+where
+    T: Serialize + for<'de> Deserialize<'de>,
+    TSVStorageManager: HistoryStorageManager<T>,
+    PSQLStorageManager: HistoryStorageManager<T>,
+    SQLiteStorageManager: HistoryStorageManager<T>,
+{
+    async fn update_snapshot(&self, snapshot: &Snapshot<T>) -> Result<()> {
+        dispatch!(self, storage_manager => storage_manager.update_snapshot(snapshot).await)
+    }
+
+    async fn get_snapshot(&self, date: NaiveDate) -> Result<Option<Snapshot<T>>> {
+        dispatch!(self, storage_manager => storage_manager.get_snapshot(date).await)
+    }
+
+    async fn get_snapshot_dates(&self) -> Result<Vec<NaiveDate>> {
+        dispatch!(self, storage_manager => storage_manager.get_snapshot_dates().await)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+        storage::{
+            common::{StorageManager, StorageType},
+            sqlite::SQLiteStorageSettings,
+            tsv::TSVStorageSettings,
+        },
+    };
+
+    use super::AnyStorageManager;
+
+    #[tokio::test]
+    async fn from_settings() {
+        let dirpath = "./temp/test/storage/factory/from_settings/";
+        std::fs::create_dir_all(dirpath).unwrap();
+
+        let symbols_storage_manager =
+            AnyStorageManager::from_settings(StorageType::TSV(TSVStorageSettings {
+                file_path: PathBuf::from(dirpath.to_string() + "symbols.tsv"),
+            }))
+            .await
+            .unwrap();
+        assert!(matches!(symbols_storage_manager, AnyStorageManager::TSV(_)));
+
+        let conversion_rates_storage_manager =
+            AnyStorageManager::from_settings(StorageType::SQLITE(SQLiteStorageSettings {
+                file_path: PathBuf::from(dirpath.to_string() + "conversion_rates.db"),
+            }))
+            .await
+            .unwrap();
+        assert!(matches!(
+            conversion_rates_storage_manager,
+            AnyStorageManager::SQLITE(_)
+        ));
+
+        let symbols = vec![Symbols {
+            code: "EUR".to_string(),
+            name: "Euro".to_string(),
+        }];
+        let conversion_rates = vec![ConversionRate {
+            from: "EUR".to_string(),
+            to: "USD".to_string(),
+            rate: dec!(1.08),
+        }];
+
+        symbols_storage_manager.update(&symbols).await.unwrap();
+        conversion_rates_storage_manager
+            .update(&conversion_rates)
+            .await
+            .unwrap();
+
+        let res: Vec<Symbols> = symbols_storage_manager.get_all().await.unwrap();
+        assert_eq!(res, symbols);
+        let res: Vec<ConversionRate> = conversion_rates_storage_manager.get_all().await.unwrap();
+        assert_eq!(res, conversion_rates);
+
+        // schema version of databases only
+        assert_eq!(
+            symbols_storage_manager.schema_version().await.unwrap(),
+            None
+        );
+        assert_eq!(
+            conversion_rates_storage_manager
+                .schema_version()
+                .await
+                .unwrap(),
+            crate::storage::sqlite::MIGRATOR
+                .iter()
+                .map(|m| m.version)
+                .max()
+        );
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+}
@@ -0,0 +1,503 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteRow};
+use sqlx::{query, query_as, Row, Sqlite, SqlitePool, Transaction};
+
+use crate::{
+    common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+    error::Result,
+};
+
+use super::common::{
+    DataInfo, DataInfoError, DataInfoSuccess, HistoryStorageManager, Snapshot, StorageManager,
+};
+
+/// Settings for SQLiteStorageManager
+#[derive(Debug, Deserialize, Serialize, Eq, PartialEq, Clone)]
+pub struct SQLiteStorageSettings {
+    pub file_path: PathBuf,
+}
+
+/// Migrations of the SQLite schema (embedded in the binary)
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations_sqlite");
+
+/// Store data in a SQLite database file
+pub struct SQLiteStorageManager {
+    pool: SqlitePool,
+}
+
+impl SQLiteStorageManager {
+    /// Build a SQLiteStorageManager from settings (the database file is created and migrated if needed)
+    pub async fn from_settings(settings: SQLiteStorageSettings) -> Result<SQLiteStorageManager> {
+        let options = SqliteConnectOptions::new()
+            .filename(&settings.file_path)
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new().connect_with(options).await?;
+
+        let storage_manager = SQLiteStorageManager { pool };
+        storage_manager.migrate().await?;
+
+        Ok(storage_manager)
+    }
+
+    /// Apply pending migrations
+    pub async fn migrate(&self) -> Result<()> {
+        MIGRATOR.run(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Version of the last migration applied
+    pub async fn schema_version(&self) -> Result<Option<i64>> {
+        Ok(
+            sqlx::query_scalar("Select max(version) From _sqlx_migrations where success")
+                .fetch_one(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn insert_one_symbol(
+        &self,
+        data: &Symbols,
+        tx: &mut Transaction<'static, Sqlite>,
+    ) -> Result<()> {
+        query(r#"Insert Into symbols (code, "name") Values ($1, $2)"#)
+            .bind(&data.code)
+            .bind(&data.name)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    async fn insert_one_conversion_rate(
+        &self,
+        data: &ConversionRate,
+        effective_date: NaiveDate,
+        tx: &mut Transaction<'static, Sqlite>,
+    ) -> Result<()> {
+        query(
+            r#"Insert Into conversions_rates ("from", "to", rate, effective_date) Values ($1, $2, $3, $4)"#,
+        )
+        .bind(&data.from)
+        .bind(&data.to)
+        .bind(data.rate.to_string())
+        .bind(effective_date)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn update_data_info(
+        &self,
+        data_name: &str,
+        tx: &mut Transaction<'static, Sqlite>,
+    ) -> Result<()> {
+        query(
+            r#"INSERT INTO data_info
+                (data_name, last_update_date)
+                Values
+                ($1, unixepoch())
+                ON CONFLICT (data_name)
+                DO
+                UPDATE Set last_update_date = unixepoch();"#,
+        )
+        .bind(data_name)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Get information about data stored in table `data_name` (`filter` restricts the lines counted)
+    async fn get_data_info_from(&self, data_name: &str, filter: &str) -> Result<DataInfo> {
+        let res: Option<DataInfoSuccess> = query_as(&format!(
+            r#"Select unixepoch() - last_update_date as seconds_since_last_update, (Select count(*) From {data_name} {filter}) as number_of_line from data_info where data_name = $1"#
+        ))
+        .bind(data_name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(match res {
+            Some(info) => DataInfo::Success(info),
+            None => DataInfo::Error(DataInfoError {
+                error: anyhow!("{data_name} has never been updated"),
+            }),
+        })
+    }
+}
+
+impl StorageManager<Symbols> for SQLiteStorageManager {
+    async fn update(&self, data: &[Symbols]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        // Delete all from symbols
+        query("Delete from symbols").execute(&mut *tx).await?;
+
+        for item in data {
+            self.insert_one_symbol(item, &mut tx).await?;
+        }
+
+        self.update_data_info("symbols", &mut tx).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_all(&self) -> Result<Vec<Symbols>> {
+        let res: Vec<Symbols> = query_as::<_, Symbols>(r#"Select code, "name" from symbols"#)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    async fn get_data_info(&self) -> Result<DataInfo> {
+        self.get_data_info_from("symbols", "").await
+    }
+}
+
+impl StorageManager<ConversionRate> for SQLiteStorageManager {
+    async fn update(&self, data: &[ConversionRate]) -> Result<()> {
+        self.update_snapshot(&Snapshot {
+            date: chrono::Utc::now().date_naive(),
+            data: data.to_vec(),
+        })
+        .await
+    }
+
+    /// Get the latest snapshot
+    async fn get_all(&self) -> Result<Vec<ConversionRate>> {
+        let rows: Vec<(String, String, String)> = query_as(
+            r#"Select "from", "to", rate from conversions_rates where effective_date = (Select max(effective_date) From conversions_rates)"#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        from_rows(rows)
+    }
+
+    async fn get_data_info(&self) -> Result<DataInfo> {
+        self.get_data_info_from(
+            "conversions_rates",
+            "where effective_date = (Select max(effective_date) From conversions_rates)",
+        )
+        .await
+    }
+}
+
+impl HistoryStorageManager<ConversionRate> for SQLiteStorageManager {
+    async fn update_snapshot(&self, snapshot: &Snapshot<ConversionRate>) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        // Older snapshots (backfilled history) don't refresh the last update of current data
+        let latest_date: Option<NaiveDate> =
+            sqlx::query_scalar("Select max(effective_date) From conversions_rates")
+                .fetch_one(&mut *tx)
+                .await?;
+        let is_latest = latest_date.is_none_or(|latest| snapshot.date >= latest);
+
+        // Delete only the snapshot with the same date (others are kept as history)
+        query("Delete from conversions_rates where effective_date = $1")
+            .bind(snapshot.date)
+            .execute(&mut *tx)
+            .await?;
+
+        for item in &snapshot.data {
+            self.insert_one_conversion_rate(item, snapshot.date, &mut tx)
+                .await?;
+        }
+
+        if is_latest {
+            self.update_data_info("conversions_rates", &mut tx).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_snapshot(&self, date: NaiveDate) -> Result<Option<Snapshot<ConversionRate>>> {
+        let effective_date: Option<NaiveDate> = sqlx::query_scalar(
+            "Select max(effective_date) From conversions_rates where effective_date <= $1",
+        )
+        .bind(date)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let Some(effective_date) = effective_date else {
+            return Ok(None);
+        };
+
+        let rows: Vec<(String, String, String)> = query_as(
+            r#"Select "from", "to", rate from conversions_rates where effective_date = $1"#,
+        )
+        .bind(effective_date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Some(Snapshot {
+            date: effective_date,
+            data: from_rows(rows)?,
+        }))
+    }
+
+    async fn get_snapshot_dates(&self) -> Result<Vec<NaiveDate>> {
+        Ok(sqlx::query_scalar(
+            "Select distinct effective_date From conversions_rates order by effective_date",
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+}
+
+/// Convert rows (from, to, rate) to conversion rates (rates are stored as text because SQLite has no decimal type)
+fn from_rows(rows: Vec<(String, String, String)>) -> Result<Vec<ConversionRate>> {
+    rows.into_iter()
+        .map(|(from, to, rate)| {
+            Ok(ConversionRate {
+                from,
+                to,
+                rate: Decimal::from_str(&rate)?,
+            })
+        })
+        .collect()
+}
+
+impl sqlx::FromRow<'_, SqliteRow> for DataInfoSuccess {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        let seconds_since_last_update: i64 = row.try_get("seconds_since_last_update")?;
+        // Clock can go backward : consider data as just updated
+        let seconds_since_last_update =
+            Duration::from_secs(seconds_since_last_update.try_into().unwrap_or(0));
+        let number_of_line: i64 = row.try_get("number_of_line")?;
+        // Impossible to have a bad value
+        let number_of_line: usize = number_of_line.try_into().unwrap();
+        Ok(Self {
+            seconds_since_last_update,
+            number_of_line,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{path::PathBuf, time::Duration};
+
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+
+    use crate::{
+        common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+        storage::common::{DataInfo, HistoryStorageManager, Snapshot, StorageManager},
+    };
+
+    use super::{SQLiteStorageManager, SQLiteStorageSettings};
+
+    async fn setup(dirpath: &str) -> SQLiteStorageManager {
+        std::fs::create_dir_all(dirpath).unwrap();
+
+        let mut file_path = PathBuf::new();
+        file_path.push(dirpath.to_string() + "/test.db");
+
+        SQLiteStorageManager::from_settings(SQLiteStorageSettings { file_path })
+            .await
+            .unwrap()
+    }
+
+    fn end(dirpath: &str) {
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+
+    #[tokio::test]
+    async fn update_symbols() {
+        let dirpath = "./temp/test/storage/sqlite/update_symbols";
+        let sm = setup(dirpath).await;
+
+        let data = vec![
+            Symbols {
+                code: "EUR".to_string(),
+                name: "Euro".to_string(),
+            },
+            Symbols {
+                code: "USD".to_string(),
+                name: "United States Dollar".to_string(),
+            },
+        ];
+
+        // update twice to check that old data are replaced
+        assert!(sm.update(&data).await.is_ok());
+        assert!(sm.update(&data).await.is_ok());
+
+        let mut res = StorageManager::<Symbols>::get_all(&sm).await.unwrap();
+        res.sort();
+
+        assert_eq!(res, data);
+
+        end(dirpath);
+    }
+
+    #[tokio::test]
+    async fn update_conversion_rates() {
+        let dirpath = "./temp/test/storage/sqlite/update_conversion_rates";
+        let sm = setup(dirpath).await;
+
+        let data = vec![
+            ConversionRate {
+                from: "EUR".to_string(),
+                to: "TBH".to_string(),
+                rate: Decimal::new(3912345678, 8),
+            },
+            ConversionRate {
+                from: "EUR".to_string(),
+                to: "USD".to_string(),
+                rate: Decimal::new(108, 2),
+            },
+        ];
+
+        assert!(sm.update(&data).await.is_ok());
+        assert!(sm.update(&data).await.is_ok());
+
+        let mut res = StorageManager::<ConversionRate>::get_all(&sm)
+            .await
+            .unwrap();
+        res.sort();
+
+        assert_eq!(res, data);
+
+        end(dirpath);
+    }
+
+    #[tokio::test]
+    async fn get_data_info_success() {
+        let dirpath = "./temp/test/storage/sqlite/get_data_info_success";
+        let sm = setup(dirpath).await;
+
+        let data = vec![Symbols {
+            code: "EUR".to_string(),
+            name: "Euro".to_string(),
+        }];
+
+        sm.update(&data).await.unwrap();
+
+        let res = StorageManager::<Symbols>::get_data_info(&sm).await;
+
+        assert!(res.is_ok());
+        match res.unwrap() {
+            DataInfo::Success(info) => assert_eq!(info.number_of_line, 1),
+            DataInfo::Error(e) => panic!("{:?}", e),
+        }
+
+        end(dirpath);
+    }
+
+    #[tokio::test]
+    async fn get_data_info_error() {
+        let dirpath = "./temp/test/storage/sqlite/get_data_info_error";
+        let sm = setup(dirpath).await;
+
+        let res = StorageManager::<ConversionRate>::get_data_info(&sm).await;
+
+        assert!(res.is_ok());
+        assert!(matches!(res.unwrap(), DataInfo::Error(..)));
+
+        end(dirpath);
+    }
+
+    #[tokio::test]
+    async fn update_snapshot_keep_history() {
+        let dirpath = "./temp/test/storage/sqlite/update_snapshot_keep_history";
+        let sm = setup(dirpath).await;
+
+        let rates = |rate: i64| {
+            vec![ConversionRate {
+                from: "EUR".to_string(),
+                to: "USD".to_string(),
+                rate: Decimal::new(rate, 2),
+            }]
+        };
+        let first = Snapshot {
+            date: NaiveDate::from_ymd_opt(2024, 9, 2).unwrap(),
+            data: rates(107),
+        };
+        let second = Snapshot {
+            date: NaiveDate::from_ymd_opt(2024, 9, 5).unwrap(),
+            data: rates(108),
+        };
+
+        assert!(sm.update_snapshot(&first).await.is_ok());
+        assert!(sm.update_snapshot(&second).await.is_ok());
+        // replace a snapshot with the same date
+        assert!(sm.update_snapshot(&second).await.is_ok());
+
+        // latest snapshot
+        let res = StorageManager::<ConversionRate>::get_all(&sm)
+            .await
+            .unwrap();
+        assert_eq!(res, second.data);
+
+        // exact date
+        let res = sm.get_snapshot(second.date).await.unwrap();
+        assert_eq!(res, Some(second.clone()));
+
+        // nearest earlier snapshot
+        let res = sm
+            .get_snapshot(NaiveDate::from_ymd_opt(2024, 9, 4).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res, Some(first));
+
+        // no snapshot before
+        let res = sm
+            .get_snapshot(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res, None);
+
+        end(dirpath);
+    }
+
+    #[tokio::test]
+    async fn update_older_snapshot_keep_last_update() {
+        let dirpath = "./temp/test/storage/sqlite/update_older_snapshot_keep_last_update";
+        let sm = setup(dirpath).await;
+
+        let snapshot = |day: u32| Snapshot {
+            date: NaiveDate::from_ymd_opt(2024, 9, day).unwrap(),
+            data: vec![ConversionRate {
+                from: "EUR".to_string(),
+                to: "USD".to_string(),
+                rate: Decimal::new(108, 2),
+            }],
+        };
+        let seconds_since_last_update = || async {
+            match StorageManager::<ConversionRate>::get_data_info(&sm)
+                .await
+                .unwrap()
+            {
+                DataInfo::Success(info) => info.seconds_since_last_update,
+                DataInfo::Error(e) => panic!("{:?}", e),
+            }
+        };
+
+        assert!(sm.update_snapshot(&snapshot(5)).await.is_ok());
+        // current data updated a day ago
+        sqlx::query("Update data_info set last_update_date = unixepoch() - 86400")
+            .execute(&sm.pool)
+            .await
+            .unwrap();
+
+        // backfilled history
+        assert!(sm.update_snapshot(&snapshot(2)).await.is_ok());
+        assert!(seconds_since_last_update().await >= Duration::from_secs(86400));
+
+        // newer data
+        assert!(sm.update_snapshot(&snapshot(6)).await.is_ok());
+        assert!(seconds_since_last_update().await < Duration::from_secs(86400));
+
+        end(dirpath);
+    }
+}
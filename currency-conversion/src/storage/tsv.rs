@@ -1,12 +1,22 @@
-use std::path::{Path, PathBuf};
-
+use std::{
+    fs::{File, OpenOptions, TryLockError},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
-use crate::common::{conversion_rate::ConversionRate, supported_symbols::Symbols};
-
-use super::common::{DataInfo, DataInfoError, DataInfoSuccess, StorageManager};
+use crate::{
+    common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+    error::Result,
+};
 
-use anyhow::{anyhow, Result};
+use super::common::{
+    DataInfo, DataInfoError, DataInfoSuccess, HistoryStorageManager, Snapshot, StorageManager,
+};
 
 /// Store data in tsv file
 pub struct TSVStorageManager {
@@ -31,14 +41,62 @@ impl TSVStorageManager {
     pub fn from_settings(settings: TSVStorageSettings) -> Result<TSVStorageManager> {
         Ok(TSVStorageManager { settings })
     }
+
+    /// Directory where snapshots are stored, one file by date (e.g. `conversion_rates_history/2024-09-05.tsv` for `conversion_rates.tsv`)
+    fn history_dir_path(&self) -> PathBuf {
+        let file_stem = self
+            .settings
+            .file_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy();
+
+        self.settings
+            .file_path
+            .with_file_name(format!("{file_stem}_history"))
+    }
+
+    /// Take the update lock of the file in settings (see [`lock_update`])
+    async fn lock_update(&self) -> Result<File> {
+        lock_update(&self.settings.file_path).await
+    }
+
+    /// Get dates of all stored snapshots (sorted)
+    fn list_snapshot_dates(&self) -> Result<Vec<NaiveDate>> {
+        let dir_path = self.history_dir_path();
+
+        if !dir_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut res = Vec::new();
+        for entry in std::fs::read_dir(dir_path)? {
+            let path = entry?.path();
+            let date = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok());
+            if let Some(date) = date {
+                res.push(date);
+            }
+        }
+        res.sort();
+
+        Ok(res)
+    }
 }
 
 impl StorageManager<ConversionRate> for TSVStorageManager {
-    async fn update(&self, data: &[ConversionRate]) -> anyhow::Result<()> {
-        create_or_update_file::<ConversionRate>(data, Path::new(&self.settings.file_path))
+    async fn update(&self, data: &[ConversionRate]) -> Result<()> {
+        self.update_snapshot(&Snapshot {
+            date: chrono::Utc::now().date_naive(),
+            data: data.to_vec(),
+        })
+        .await
     }
 
-    async fn get_all(&self) -> anyhow::Result<Vec<ConversionRate>> {
+    /// Get the latest snapshot
+    async fn get_all(&self) -> Result<Vec<ConversionRate>> {
         load_data(Path::new(&self.settings.file_path))
     }
 
@@ -47,12 +105,63 @@ impl StorageManager<ConversionRate> for TSVStorageManager {
     }
 }
 
+/// The file in settings always contains the latest snapshot, history is stored in [`TSVStorageManager::history_dir_path`]
+impl HistoryStorageManager<ConversionRate> for TSVStorageManager {
+    async fn update_snapshot(&self, snapshot: &Snapshot<ConversionRate>) -> Result<()> {
+        let _lock = self.lock_update().await?;
+
+        let is_latest = self
+            .list_snapshot_dates()?
+            .last()
+            .is_none_or(|latest| snapshot.date >= *latest);
+
+        let dir_path = self.history_dir_path();
+        std::fs::create_dir_all(&dir_path)?;
+        create_or_update_file(
+            &snapshot.data,
+            &dir_path.join(format!("{}.tsv", snapshot.date.format("%Y-%m-%d"))),
+        )?;
+
+        if is_latest {
+            create_or_update_file(&snapshot.data, Path::new(&self.settings.file_path))?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_snapshot(&self, date: NaiveDate) -> Result<Option<Snapshot<ConversionRate>>> {
+        let effective_date = self
+            .list_snapshot_dates()?
+            .into_iter()
+            .rev()
+            .find(|snapshot_date| *snapshot_date <= date);
+
+        match effective_date {
+            Some(effective_date) => Ok(Some(Snapshot {
+                date: effective_date,
+                data: load_data(
+                    &self
+                        .history_dir_path()
+                        .join(format!("{}.tsv", effective_date.format("%Y-%m-%d"))),
+                )?,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_snapshot_dates(&self) -> Result<Vec<NaiveDate>> {
+        self.list_snapshot_dates()
+    }
+}
+
 impl StorageManager<Symbols> for TSVStorageManager {
-    async fn update(&self, data: &[Symbols]) -> anyhow::Result<()> {
+    async fn update(&self, data: &[Symbols]) -> Result<()> {
+        let _lock = self.lock_update().await?;
+
         create_or_update_file::<Symbols>(data, Path::new(&self.settings.file_path))
     }
 
-    async fn get_all(&self) -> anyhow::Result<Vec<Symbols>> {
+    async fn get_all(&self) -> Result<Vec<Symbols>> {
         load_data(Path::new(&self.settings.file_path))
     }
 
@@ -61,19 +170,78 @@ impl StorageManager<Symbols> for TSVStorageManager {
     }
 }
 
-/// Write a Vec in a tsv file                                                                                
+/// Delay between two attempts to take the update lock
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+/// Take an exclusive advisory lock on `<file>.lock` (waiting for other updates to end), it is released when the returned file is dropped.
+/// The lock is retried without blocking the async runtime while an other update holds it.
+async fn lock_update(path: &Path) -> Result<File> {
+    let mut lock_path = path.as_os_str().to_owned();
+    lock_path.push(".lock");
+
+    if let Some(dir_path) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir_path)?;
+    }
+
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)?;
+    tracing::debug!("Waiting for lock {:?}", lock_path);
+    loop {
+        match lock.try_lock() {
+            Ok(()) => return Ok(lock),
+            Err(TryLockError::WouldBlock) => tokio::time::sleep(LOCK_RETRY_DELAY).await,
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+    }
+}
+
+/// Write a Vec in a tsv file.
+/// Data are written in a temporary file renamed over `path`, so readers never see a partially written file.
 fn create_or_update_file<T>(data: &[T], path: &Path) -> Result<()>
 where
     T: Serialize,
 {
+    // temporary files are unique by process and by write
+    static WRITE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
     tracing::info!("Updating {:?}", path);
-    let mut wrt = csv::WriterBuilder::new().delimiter(b'\t').from_path(path)?;
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp_path = path.with_file_name(format!(
+        ".{file_name}.{}.{}.tmp",
+        std::process::id(),
+        WRITE_COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let res = write_file(data, &tmp_path).and_then(|_| Ok(std::fs::rename(&tmp_path, path)?));
+    if res.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    res?;
+
+    tracing::info!("Updated {:?}", path);
+
+    Ok(())
+}
+
+/// Write a Vec in a new tsv file and flush it to disk
+fn write_file<T>(data: &[T], path: &Path) -> Result<()>
+where
+    T: Serialize,
+{
+    let mut wrt = csv::WriterBuilder::new()
+        .delimiter(b'\t')
+        .from_writer(File::create(path)?);
 
     for row in data {
         wrt.serialize(row)?;
     }
 
-    tracing::info!("Updated {:?}", path);
+    let mut file = wrt.into_inner().map_err(|e| e.into_error())?;
+    file.flush()?;
+    file.sync_all()?;
 
     Ok(())
 }
@@ -106,13 +274,16 @@ where
     match file_metadata {
         Ok(metadata) => match load_data::<T>(Path::new(path)) {
             Ok(data) => Ok(DataInfo::Success(DataInfoSuccess {
-                seconds_since_last_update: metadata.modified()?.elapsed()?,
+                seconds_since_last_update: metadata
+                    .modified()?
+                    .elapsed()
+                    .map_err(std::io::Error::other)?,
                 number_of_line: data.len(),
             })),
             #[cfg(not(tarpaulin_include))]
-            Err(e) => Ok(DataInfo::Error(DataInfoError { error: e })),
+            Err(e) => Ok(DataInfo::Error(DataInfoError { error: e.into() })),
         },
-        Err(e) => Ok(DataInfo::Error(DataInfoError { error: anyhow!(e) })),
+        Err(e) => Ok(DataInfo::Error(DataInfoError { error: e.into() })),
     }
 }
 
@@ -120,9 +291,16 @@ where
 mod test {
     use std::path::{Path, PathBuf};
 
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
     use serde::{Deserialize, Serialize};
 
-    use crate::storage::common::DataInfo;
+    use crate::{
+        common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+        storage::common::{DataInfo, HistoryStorageManager, Snapshot, StorageManager},
+    };
+
+    use super::TSVStorageManager;
 
     #[derive(Serialize, Deserialize, PartialEq)]
     struct TestStruct {
@@ -238,4 +416,190 @@ mod test {
 
         std::fs::remove_dir_all(dirpath).unwrap();
     }
+
+    #[tokio::test]
+    async fn update_snapshot_keep_history() {
+        let dirpath = "./temp/test/storage/tsv/update_snapshot_keep_history";
+
+        std::fs::create_dir_all(dirpath).unwrap();
+
+        let mut path = PathBuf::new();
+        path.push(dirpath.to_string() + "/conversion_rates.tsv");
+
+        let sm = TSVStorageManager::build(path.clone());
+
+        let rates = |rate: i64| {
+            vec![ConversionRate {
+                from: "EUR".to_string(),
+                to: "USD".to_string(),
+                rate: Decimal::new(rate, 2),
+            }]
+        };
+        let first = Snapshot {
+            date: NaiveDate::from_ymd_opt(2024, 9, 2).unwrap(),
+            data: rates(107),
+        };
+        let second = Snapshot {
+            date: NaiveDate::from_ymd_opt(2024, 9, 5).unwrap(),
+            data: rates(108),
+        };
+
+        assert!(sm.update_snapshot(&second).await.is_ok());
+        // an older snapshot doesn't replace the latest one
+        assert!(sm.update_snapshot(&first).await.is_ok());
+
+        assert!(
+            Path::new(&(dirpath.to_string() + "/conversion_rates_history/2024-09-02.tsv")).exists()
+        );
+
+        // latest snapshot
+        let res = StorageManager::<ConversionRate>::get_all(&sm)
+            .await
+            .unwrap();
+        assert_eq!(res, second.data);
+
+        // exact date
+        let res = sm.get_snapshot(second.date).await.unwrap();
+        assert_eq!(res, Some(second.clone()));
+
+        // nearest earlier snapshot
+        let res = sm
+            .get_snapshot(NaiveDate::from_ymd_opt(2024, 9, 4).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res, Some(first));
+
+        // no snapshot before
+        let res = sm
+            .get_snapshot(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res, None);
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn concurrent_writes_and_reads() {
+        let dirpath = "./temp/test/storage/tsv/concurrent_writes_and_reads";
+
+        std::fs::create_dir_all(dirpath).unwrap();
+
+        let path = PathBuf::from(dirpath.to_string() + "/test.tsv");
+
+        let data = |len: u8| {
+            (0..len)
+                .map(|code| TestStruct {
+                    code,
+                    name: code.to_string().repeat(100),
+                })
+                .collect::<Vec<_>>()
+        };
+        super::create_or_update_file(&data(100), &path).unwrap();
+
+        let writers = (0..2).map(|_| {
+            let path = path.clone();
+            tokio::spawn(async move {
+                for i in 0..50 {
+                    let _lock = super::lock_update(&path).await.unwrap();
+                    let len = if i % 2 == 0 { 200 } else { 100 };
+                    super::create_or_update_file(&data(len), &path).unwrap();
+                }
+            })
+        });
+        let readers = (0..4).map(|_| {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || {
+                for _ in 0..100 {
+                    // a complete file is always read
+                    let res = super::load_data::<TestStruct>(&path).unwrap();
+                    assert!(res.len() == 100 || res.len() == 200);
+                }
+            })
+        });
+        let tasks: Vec<_> = writers.chain(readers).collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // no temporary file left
+        let files: Vec<_> = std::fs::read_dir(dirpath)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert!(files.iter().all(|file| !file.ends_with(".tmp")));
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn concurrent_update_snapshots() {
+        let dirpath = "./temp/test/storage/tsv/concurrent_update_snapshots";
+
+        std::fs::create_dir_all(dirpath).unwrap();
+
+        let path = PathBuf::from(dirpath.to_string() + "/conversion_rates.tsv");
+
+        let snapshot = |day: u32| Snapshot {
+            date: NaiveDate::from_ymd_opt(2024, 9, day).unwrap(),
+            data: vec![ConversionRate {
+                from: "EUR".to_string(),
+                to: "USD".to_string(),
+                rate: Decimal::new(100 + day as i64, 2),
+            }],
+        };
+
+        let tasks: Vec<_> = (1..=20)
+            .map(|day| {
+                let sm = TSVStorageManager::build(path.clone());
+                tokio::spawn(async move { sm.update_snapshot(&snapshot(day)).await })
+            })
+            .collect();
+        for task in tasks {
+            assert!(task.await.unwrap().is_ok());
+        }
+
+        // latest snapshot wins whatever the order of updates
+        let sm = TSVStorageManager::build(path.clone());
+        let res = StorageManager::<ConversionRate>::get_all(&sm)
+            .await
+            .unwrap();
+        assert_eq!(res, snapshot(20).data);
+
+        let res = sm
+            .get_snapshot(NaiveDate::from_ymd_opt(2024, 9, 10).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res, Some(snapshot(10)));
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+
+    #[tokio::test]
+    async fn wait_lock_without_blocking_runtime() {
+        let dirpath = "./temp/test/storage/tsv/wait_lock_without_blocking_runtime";
+
+        std::fs::create_dir_all(dirpath).unwrap();
+
+        let path = PathBuf::from(dirpath.to_string() + "/symbols.tsv");
+        let lock = super::lock_update(&path).await.unwrap();
+
+        let sm = TSVStorageManager::build(path.clone());
+        let update = tokio::spawn(async move {
+            sm.update(&[Symbols {
+                code: "EUR".to_string(),
+                name: "Euro".to_string(),
+            }])
+            .await
+        });
+
+        // the single thread runtime keeps running while the update waits for the lock
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(!update.is_finished());
+
+        drop(lock);
+        assert!(update.await.unwrap().is_ok());
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
 }
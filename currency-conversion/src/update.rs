@@ -1,3 +1,6 @@
 pub mod common;
+pub mod ecb;
+pub mod exchange_rates_api;
+pub mod provider;
 pub mod update_converison_rates;
 pub mod update_symbols;
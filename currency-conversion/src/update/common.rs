@@ -1,5 +1,7 @@
 use serde::Deserialize;
-// TODO : Lock mechanism to avoid simultaneous update
+
+use crate::error::Error;
+
 /// Exchange rates API error response
 #[derive(Deserialize, Debug)]
 pub struct ErrorResponseAPI {
@@ -7,6 +9,17 @@ pub struct ErrorResponseAPI {
     pub error: ErrorInfoAPI,
 }
 
+impl ErrorResponseAPI {
+    /// Convert to a library error (`url` is the endpoint called)
+    pub fn into_error(self, url: &str) -> Error {
+        Error::ProviderApi {
+            url: url.to_string(),
+            code: self.error.code,
+            message: self.error.message,
+        }
+    }
+}
+
 /// Exchange rates API error information
 #[derive(Deserialize, Debug)]
 pub struct ErrorInfoAPI {
@@ -0,0 +1,377 @@
+use std::{collections::HashMap, str::FromStr};
+
+use chrono::NaiveDate;
+use quick_xml::events::Event;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+    error::{Error, Result},
+    storage::common::Snapshot,
+};
+
+use super::provider::RateProvider;
+
+/// Currency of the ECB reference rates
+const ECB_BASE: &str = "EUR";
+
+/// Names of the currencies published by the ECB (the feed only contains codes)
+const ECB_CURRENCIES_NAMES: [(&str, &str); 31] = [
+    ("AUD", "Australian Dollar"),
+    ("BGN", "Bulgarian Lev"),
+    ("BRL", "Brazilian Real"),
+    ("CAD", "Canadian Dollar"),
+    ("CHF", "Swiss Franc"),
+    ("CNY", "Chinese Yuan"),
+    ("CZK", "Czech Republic Koruna"),
+    ("DKK", "Danish Krone"),
+    ("EUR", "Euro"),
+    ("GBP", "British Pound Sterling"),
+    ("HKD", "Hong Kong Dollar"),
+    ("HUF", "Hungarian Forint"),
+    ("IDR", "Indonesian Rupiah"),
+    ("ILS", "Israeli New Sheqel"),
+    ("INR", "Indian Rupee"),
+    ("ISK", "Icelandic Króna"),
+    ("JPY", "Japanese Yen"),
+    ("KRW", "South Korean Won"),
+    ("MXN", "Mexican Peso"),
+    ("MYR", "Malaysian Ringgit"),
+    ("NOK", "Norwegian Krone"),
+    ("NZD", "New Zealand Dollar"),
+    ("PHP", "Philippine Peso"),
+    ("PLN", "Polish Zloty"),
+    ("RON", "Romanian Leu"),
+    ("SEK", "Swedish Krona"),
+    ("SGD", "Singapore Dollar"),
+    ("THB", "Thai Baht"),
+    ("TRY", "Turkish Lira"),
+    ("USD", "United States Dollar"),
+    ("ZAR", "South African Rand"),
+];
+
+/// Settings for ECBProvider
+#[derive(Debug, Deserialize, Serialize, Eq, PartialEq, Clone)]
+pub struct ECBProviderSettings {
+    /// url (http:// or https://) or local file path of an eurofxref-daily.xml file
+    pub url: String,
+}
+
+impl Default for ECBProviderSettings {
+    fn default() -> Self {
+        ECBProviderSettings {
+            url: "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml".to_string(),
+        }
+    }
+}
+
+/// Get symbols and conversion rates from the ECB euro reference rates (no API key needed)
+pub struct ECBProvider {
+    settings: ECBProviderSettings,
+}
+
+impl ECBProvider {
+    /// Build a ECBProvider from settings
+    pub fn from_settings(settings: ECBProviderSettings) -> ECBProvider {
+        ECBProvider { settings }
+    }
+
+    /// Get the ECB feed content from url or local file
+    async fn get_feed(&self) -> Result<String> {
+        let url = &self.settings.url;
+
+        if url.starts_with("http://") || url.starts_with("https://") {
+            let response = reqwest::get(url).await?.error_for_status()?;
+            Ok(response.text().await?)
+        } else {
+            let path = url.strip_prefix("file://").unwrap_or(url);
+            Ok(tokio::fs::read_to_string(path).await?)
+        }
+    }
+}
+
+impl RateProvider for ECBProvider {
+    async fn get_symbols(&self) -> Result<Vec<Symbols>> {
+        let rates = parse_eurofxref(&self.get_feed().await?)?;
+
+        Ok(get_symbols_from_rates(&rates.data))
+    }
+
+    async fn get_latest_conversion_rates(&self, base: &str) -> Result<Snapshot<ConversionRate>> {
+        let rates = parse_eurofxref(&self.get_feed().await?)?;
+
+        Ok(Snapshot {
+            date: rates.date,
+            data: rebase(rates.data, base)?,
+        })
+    }
+
+    async fn get_historical_conversion_rates(
+        &self,
+        _base: &str,
+        date: NaiveDate,
+    ) -> Result<Snapshot<ConversionRate>> {
+        Err(Error::Provider(format!(
+            "ECB daily feed doesn't provide conversion rates of {date} !"
+        )))
+    }
+}
+
+/// Parse an eurofxref xml file to conversion rates from EUR
+fn parse_eurofxref(content: &str) -> Result<Snapshot<ConversionRate>> {
+    let mut reader = quick_xml::Reader::from_str(content);
+    let mut date = None;
+    let mut res = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Cube" => {
+                let mut currency = None;
+                let mut rate = None;
+                for attribute in e.attributes() {
+                    let attribute = attribute?;
+                    match attribute.key.as_ref() {
+                        b"time" => {
+                            date = Some(NaiveDate::parse_from_str(
+                                &attribute.unescape_value()?,
+                                "%Y-%m-%d",
+                            )?)
+                        }
+                        b"currency" => currency = Some(attribute.unescape_value()?.to_string()),
+                        b"rate" => rate = Some(attribute.unescape_value()?.to_string()),
+                        _ => (),
+                    }
+                }
+
+                if let (Some(currency), Some(rate)) = (currency, rate) {
+                    res.push(ConversionRate {
+                        from: ECB_BASE.to_string(),
+                        to: currency,
+                        rate: Decimal::from_str(&rate)?,
+                    });
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+
+    if res.is_empty() {
+        return Err(Error::Provider(
+            "No conversion rates found in ECB feed !".to_string(),
+        ));
+    }
+
+    Ok(Snapshot {
+        date: date.ok_or(Error::Provider("No date found in ECB feed !".to_string()))?,
+        data: res,
+    })
+}
+
+/// Convert conversion rates from EUR to conversion rates from [`base`]
+fn rebase(rates: Vec<ConversionRate>, base: &str) -> Result<Vec<ConversionRate>> {
+    if base == ECB_BASE {
+        return Ok(rates);
+    }
+
+    let base_rate = rates
+        .iter()
+        .find(|rate| rate.to == base)
+        .ok_or(Error::UnknownCurrency(base.to_string()))?
+        .rate;
+
+    let mut res: Vec<ConversionRate> = rates
+        .iter()
+        .filter(|rate| rate.to != base)
+        .map(|rate| ConversionRate {
+            from: base.to_string(),
+            to: rate.to.clone(),
+            rate: rate.rate / base_rate,
+        })
+        .collect();
+
+    res.push(ConversionRate {
+        from: base.to_string(),
+        to: ECB_BASE.to_string(),
+        rate: Decimal::ONE / base_rate,
+    });
+
+    Ok(res)
+}
+
+/// Build symbols (EUR and all currencies in conversion rates)
+fn get_symbols_from_rates(rates: &[ConversionRate]) -> Vec<Symbols> {
+    let names: HashMap<&str, &str> = HashMap::from(ECB_CURRENCIES_NAMES);
+
+    std::iter::once(ECB_BASE)
+        .chain(rates.iter().map(|rate| rate.to.as_str()))
+        .map(|code| Symbols {
+            code: code.to_string(),
+            name: names.get(code).unwrap_or(&code).to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use chrono::NaiveDate;
+    use httpmock::{Method::GET, MockServer};
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+        update::provider::RateProvider,
+    };
+
+    use super::{ECBProvider, ECBProviderSettings};
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+	<gesmes:subject>Reference rates</gesmes:subject>
+	<gesmes:Sender>
+		<gesmes:name>European Central Bank</gesmes:name>
+	</gesmes:Sender>
+	<Cube>
+		<Cube time='2024-09-05'>
+			<Cube currency='USD' rate='1.1086'/>
+			<Cube currency='JPY' rate='159.22'/>
+			<Cube currency='GBP' rate='0.84293'/>
+		</Cube>
+	</Cube>
+</gesmes:Envelope>"#;
+
+    #[test]
+    fn parse_eurofxref() {
+        let res = super::parse_eurofxref(FEED);
+
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        assert_eq!(res.date, NaiveDate::from_ymd_opt(2024, 9, 5).unwrap());
+        assert_eq!(res.data.len(), 3);
+        assert!(res.data.contains(&ConversionRate {
+            from: "EUR".to_string(),
+            to: "USD".to_string(),
+            rate: dec!(1.1086),
+        }));
+    }
+
+    #[test]
+    fn parse_eurofxref_empty() {
+        let res = super::parse_eurofxref("<Cube><Cube time='2024-09-05'></Cube></Cube>");
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn rebase() {
+        let rates = super::parse_eurofxref(FEED).unwrap();
+
+        let res = super::rebase(rates.data, "USD");
+
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        assert_eq!(res.len(), 3);
+        assert!(res.iter().all(|rate| rate.from == "USD"));
+        assert!(res.contains(&ConversionRate {
+            from: "USD".to_string(),
+            to: "EUR".to_string(),
+            rate: dec!(1) / dec!(1.1086),
+        }));
+        assert!(res.contains(&ConversionRate {
+            from: "USD".to_string(),
+            to: "GBP".to_string(),
+            rate: dec!(0.84293) / dec!(1.1086),
+        }));
+    }
+
+    #[test]
+    fn rebase_unknown_base() {
+        let rates = super::parse_eurofxref(FEED).unwrap();
+
+        let res = super::rebase(rates.data, "TBH");
+
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_latest_conversion_rates() {
+        let server = MockServer::start();
+
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/eurofxref-daily.xml");
+            then.status(200)
+                .header("content-type", "text/xml")
+                .body(FEED);
+        });
+
+        let provider = ECBProvider::from_settings(ECBProviderSettings {
+            url: server.url("/eurofxref-daily.xml"),
+        });
+
+        let response = provider.get_latest_conversion_rates("EUR").await;
+
+        mock.assert();
+
+        assert!(response.is_ok());
+        assert!(response.unwrap().data.contains(&ConversionRate {
+            from: "EUR".to_string(),
+            to: "JPY".to_string(),
+            rate: dec!(159.22),
+        }));
+    }
+
+    #[tokio::test]
+    async fn get_latest_conversion_rates_fail() {
+        let server = MockServer::start();
+
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/eurofxref-daily.xml");
+            then.status(404);
+        });
+
+        let provider = ECBProvider::from_settings(ECBProviderSettings {
+            url: server.url("/eurofxref-daily.xml"),
+        });
+
+        let response = provider.get_latest_conversion_rates("EUR").await;
+
+        mock.assert();
+
+        assert!(response.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_symbols_from_file() {
+        let dirpath = "./temp/test/update/ecb/get_symbols_from_file";
+
+        std::fs::create_dir_all(dirpath).unwrap();
+
+        let mut path = PathBuf::new();
+        path.push(dirpath.to_string() + "/eurofxref-daily.xml");
+
+        std::fs::write(&path, FEED).unwrap();
+
+        let provider = ECBProvider::from_settings(ECBProviderSettings {
+            url: path.to_string_lossy().to_string(),
+        });
+
+        let response = provider.get_symbols().await;
+
+        assert!(response.is_ok());
+        let response = response.unwrap();
+        assert_eq!(response.len(), 4);
+        assert!(response.contains(&Symbols {
+            code: "EUR".to_string(),
+            name: "Euro".to_string(),
+        }));
+        assert!(response.contains(&Symbols {
+            code: "GBP".to_string(),
+            name: "British Pound Sterling".to_string(),
+        }));
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+
+use crate::error::Result;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::{
+    common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+    storage::common::Snapshot,
+};
+
+use super::{common::ErrorResponseAPI, provider::RateProvider};
+
+/// Get symbols and conversion rates from exchangeratesapi.io
+pub struct ExchangeRatesApiProvider {
+    api_key: String,
+    /// endpoint url to get supported symbols (param : {api_key})
+    symbols_endpoint_url: String,
+    /// endpoint url to get conversion rates (param : {api_key}, {base})
+    latest_endpoint_url: String,
+    /// endpoint url to get conversion rates of a past day (param : {api_key}, {base}, {date})
+    historical_endpoint_url: String,
+}
+
+impl ExchangeRatesApiProvider {
+    /// Build a ExchangeRatesApiProvider with api key and endpoints urls
+    pub fn build(
+        api_key: &str,
+        symbols_endpoint_url: &str,
+        latest_endpoint_url: &str,
+        historical_endpoint_url: &str,
+    ) -> ExchangeRatesApiProvider {
+        ExchangeRatesApiProvider {
+            api_key: api_key.to_string(),
+            symbols_endpoint_url: symbols_endpoint_url.to_string(),
+            latest_endpoint_url: latest_endpoint_url.to_string(),
+            historical_endpoint_url: historical_endpoint_url.to_string(),
+        }
+    }
+}
+
+impl RateProvider for ExchangeRatesApiProvider {
+    async fn get_symbols(&self) -> Result<Vec<Symbols>> {
+        let url = self
+            .symbols_endpoint_url
+            .replace("{api_key}", &self.api_key);
+        get_supported_symbols(&url).await
+    }
+
+    async fn get_latest_conversion_rates(&self, base: &str) -> Result<Snapshot<ConversionRate>> {
+        let url = self
+            .latest_endpoint_url
+            .replace("{api_key}", &self.api_key)
+            .replace("{base}", base);
+        get_conversion_rates(&url, base).await
+    }
+
+    async fn get_historical_conversion_rates(
+        &self,
+        base: &str,
+        date: NaiveDate,
+    ) -> Result<Snapshot<ConversionRate>> {
+        let url = self
+            .historical_endpoint_url
+            .replace("{api_key}", &self.api_key)
+            .replace("{base}", base)
+            .replace("{date}", &date.format("%Y-%m-%d").to_string());
+        // Historical endpoint response has the same format as latest endpoint response
+        get_conversion_rates(&url, base).await
+    }
+}
+
+/// Exchange rates API success response on symbols endpoint
+#[derive(Deserialize, Debug)]
+struct SuccessSymbolResponseAPI {
+    //success: bool,
+    symbols: HashMap<String, String>,
+}
+
+/// Exchange rates API response on symbols endpoint
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum SymbolResponseAPI {
+    Success(SuccessSymbolResponseAPI),
+    Fail(ErrorResponseAPI),
+}
+
+/// Exchange rates API success response on latest endpoint
+#[derive(Deserialize, Debug)]
+struct SuccessLatestResponseAPI {
+    //success: bool,
+    //timestamp: i64,
+    //base: String,
+    date: NaiveDate,
+    rates: HashMap<String, Decimal>,
+}
+
+/// Exchange rates API response on latest endpoint
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum LatestResponseAPI {
+    Success(SuccessLatestResponseAPI),
+    Fail(ErrorResponseAPI),
+}
+
+/// Get supported symbols from exchanges rates API
+async fn get_supported_symbols(url: &str) -> Result<Vec<Symbols>> {
+    let response = reqwest::get(url).await?;
+
+    match response.json().await? {
+        SymbolResponseAPI::Success(s) => Ok(
+            crate::common::supported_symbols::from_hash_map_to_vec(s.symbols)?,
+        ),
+        SymbolResponseAPI::Fail(f) => Err(f.into_error(url)),
+    }
+}
+
+/// Get conversion rates for the base currency to others currencies from exchanges rates API
+async fn get_conversion_rates(url: &str, base: &str) -> Result<Snapshot<ConversionRate>> {
+    let response = reqwest::get(url).await?;
+
+    tracing::debug!("{:?}", response);
+
+    match response.json().await? {
+        LatestResponseAPI::Success(s) => Ok(Snapshot {
+            date: s.date,
+            data: crate::common::conversion_rate::from_hash_map_to_vec(s.rates, base)?,
+        }),
+        LatestResponseAPI::Fail(f) => Err(f.into_error(url)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use httpmock::{Method::GET, MockServer};
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    use crate::{
+        common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+        update::provider::RateProvider,
+    };
+
+    use super::ExchangeRatesApiProvider;
+
+    #[tokio::test]
+    async fn get_supported_symbols() {
+        // param
+        let api_key = "123";
+
+        let server_response = json!(
+        {
+          "success": true,
+          "symbols": {
+            "AED": "United Arab Emirates Dirham",
+            "AFN": "Afghan Afghani",
+            "ALL": "Albanian Lek",
+            "AMD": "Armenian Dram",
+            }
+        }
+        );
+        let expected_all = Symbols {
+            code: "ALL".to_string(),
+            name: "Albanian Lek".to_string(),
+        };
+
+        let server = MockServer::start();
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/symbols")
+                .query_param("access_key", api_key);
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(server_response);
+        });
+
+        let response =
+            super::get_supported_symbols(&server.url(format!("/symbols?access_key={api_key}")))
+                .await;
+
+        mock.assert();
+
+        assert!(response.is_ok());
+        assert!(response.unwrap().contains(&expected_all));
+    }
+
+    #[tokio::test]
+    async fn get_supported_symbols_fail() {
+        // param
+        let api_key = "123";
+        let error_code = 104;
+        let error_info =
+            "Your monthly API request volume has been reached. Please upgrade your plan.";
+        let server_response = json!({
+          "success": false,
+          "error": {
+            "code": "104",
+            "message": "Your monthly API request volume has been reached. Please upgrade your plan."
+          }
+        });
+
+        let server = MockServer::start();
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/test")
+                .query_param("access_key", api_key);
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(server_response);
+        });
+
+        let expected_error_message = format!(
+            "Call {} failed : {} - {}",
+            server.url(format!("/test?access_key={api_key}")),
+            error_code,
+            error_info
+        );
+
+        let response =
+            super::get_supported_symbols(&server.url(format!("/test?access_key={api_key}"))).await;
+
+        mock.assert();
+
+        assert!(response.is_err());
+        assert_eq!(response.unwrap_err().to_string(), expected_error_message);
+    }
+
+    #[tokio::test]
+    async fn get_conversion_rates() {
+        // param
+        let api_key = "123";
+        let base = "EUR";
+        let server_response = json!({
+            "success": true,
+            "timestamp": 1519296206,
+            "base": "EUR",
+            "date": "2021-03-17",
+            "rates": {
+                "GBP": 0.72007,
+                "JPY": 107.346001,
+                "USD": 0.813399,
+            }
+        });
+        let expected_usd = ConversionRate {
+            from: base.to_string(),
+            to: "USD".to_string(),
+            rate: dec!(0.813399),
+        };
+
+        let server = MockServer::start();
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/test")
+                .query_param("access_key", api_key)
+                .query_param("base", base);
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(server_response);
+        });
+
+        let response = super::get_conversion_rates(
+            &server.url(format!("/test?access_key={api_key}&base={base}")),
+            base,
+        )
+        .await;
+
+        mock.assert();
+
+        assert!(response.is_ok());
+        let response = response.unwrap();
+        assert_eq!(response.date, NaiveDate::from_ymd_opt(2021, 3, 17).unwrap());
+        assert!(response.data.contains(&expected_usd));
+    }
+
+    #[tokio::test]
+    async fn get_conversion_rates_fail() {
+        // param
+        let api_key = "123";
+        let base = "EUR";
+        let error_code = 104;
+        let error_info =
+            "Your monthly API request volume has been reached. Please upgrade your plan.";
+        let server_response = json!({
+          "success": false,
+          "error": {
+            "code": "104",
+            "message": "Your monthly API request volume has been reached. Please upgrade your plan."
+          }
+        });
+
+        let server = MockServer::start();
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/test")
+                .query_param("access_key", api_key)
+                .query_param("base", base);
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(server_response);
+        });
+
+        let expected_error_message = format!(
+            "Call {} failed : {} - {}",
+            server.url(format!("/test?access_key={api_key}&base={base}")),
+            error_code,
+            error_info
+        );
+
+        let response = super::get_conversion_rates(
+            &server.url(format!("/test?access_key={api_key}&base={base}")),
+            base,
+        )
+        .await;
+
+        mock.assert();
+
+        assert!(response.is_err());
+        assert_eq!(response.unwrap_err().to_string(), expected_error_message);
+    }
+
+    #[tokio::test]
+    async fn provider_replace_url_params() {
+        // param
+        let api_key = "123";
+        let base = "USD";
+        let server_response = json!({
+            "success": true,
+            "timestamp": 1519296206,
+            "base": "USD",
+            "date": "2021-03-17",
+            "rates": {
+                "EUR": 0.92,
+            }
+        });
+
+        let server = MockServer::start();
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/latest")
+                .query_param("access_key", api_key)
+                .query_param("base", base);
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(server_response);
+        });
+
+        let provider = ExchangeRatesApiProvider::build(
+            api_key,
+            &server.url("/symbols?access_key={api_key}"),
+            &server.url("/latest?access_key={api_key}&base={base}"),
+            &server.url("/{date}?access_key={api_key}&base={base}"),
+        );
+
+        let response = provider.get_latest_conversion_rates(base).await;
+
+        mock.assert();
+
+        assert!(response.is_ok());
+        assert_eq!(response.unwrap().data[0].from, base);
+    }
+
+    #[tokio::test]
+    async fn get_historical_conversion_rates() {
+        // param
+        let api_key = "123";
+        let base = "EUR";
+        let server_response = json!({
+            "success": true,
+            "historical": true,
+            "date": "2013-12-24",
+            "timestamp": 1387929599,
+            "base": "EUR",
+            "rates": {
+                "USD": 1.367894,
+                "GBP": 0.838005,
+            }
+        });
+
+        let server = MockServer::start();
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/2013-12-24")
+                .query_param("access_key", api_key)
+                .query_param("base", base);
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(server_response);
+        });
+
+        let provider = ExchangeRatesApiProvider::build(
+            api_key,
+            &server.url("/symbols?access_key={api_key}"),
+            &server.url("/latest?access_key={api_key}&base={base}"),
+            &server.url("/{date}?access_key={api_key}&base={base}"),
+        );
+
+        let date = NaiveDate::from_ymd_opt(2013, 12, 24).unwrap();
+        let response = provider.get_historical_conversion_rates(base, date).await;
+
+        mock.assert();
+
+        assert!(response.is_ok());
+        let response = response.unwrap();
+        assert_eq!(response.date, date);
+        assert!(response.data.contains(&ConversionRate {
+            from: base.to_string(),
+            to: "USD".to_string(),
+            rate: dec!(1.367894),
+        }));
+    }
+}
@@ -0,0 +1,91 @@
+use std::future::Future;
+
+use crate::error::Result;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+    storage::common::Snapshot,
+};
+
+use super::{
+    ecb::{ECBProvider, ECBProviderSettings},
+    exchange_rates_api::ExchangeRatesApiProvider,
+};
+
+/// Interface to get symbols and conversion rates from a provider
+pub trait RateProvider {
+    /// Get all supported symbols
+    fn get_symbols(&self) -> impl Future<Output = Result<Vec<Symbols>>>;
+
+    /// Get latest conversion rates from `base` currency to others currencies (with their effective date)
+    fn get_latest_conversion_rates(
+        &self,
+        base: &str,
+    ) -> impl Future<Output = Result<Snapshot<ConversionRate>>>;
+
+    /// Get conversion rates from `base` currency to others currencies effective at `date`
+    fn get_historical_conversion_rates(
+        &self,
+        base: &str,
+        date: NaiveDate,
+    ) -> impl Future<Output = Result<Snapshot<ConversionRate>>>;
+}
+
+/// Provider available
+#[derive(Debug, Deserialize, Serialize, Eq, PartialEq, Clone, Default)]
+pub enum ProviderType {
+    /// exchangeratesapi.io (or any API with the same response format)
+    #[default]
+    ExchangeRatesApi,
+    /// ECB euro reference rates (no API key needed)
+    ECB(ECBProviderSettings),
+}
+
+impl ProviderType {
+    /// Indicate if the provider needs an API key
+    pub fn requires_api_key(&self) -> bool {
+        match self {
+            ProviderType::ExchangeRatesApi => true,
+            ProviderType::ECB(_) => false,
+        }
+    }
+}
+
+/// Any provider, to select one at runtime
+pub enum Provider {
+    ExchangeRatesApi(ExchangeRatesApiProvider),
+    ECB(ECBProvider),
+}
+
+impl RateProvider for Provider {
+    async fn get_symbols(&self) -> Result<Vec<Symbols>> {
+        match self {
+            Provider::ExchangeRatesApi(provider) => provider.get_symbols().await,
+            Provider::ECB(provider) => provider.get_symbols().await,
+        }
+    }
+
+    async fn get_latest_conversion_rates(&self, base: &str) -> Result<Snapshot<ConversionRate>> {
+        match self {
+            Provider::ExchangeRatesApi(provider) => {
+                provider.get_latest_conversion_rates(base).await
+            }
+            Provider::ECB(provider) => provider.get_latest_conversion_rates(base).await,
+        }
+    }
+
+    async fn get_historical_conversion_rates(
+        &self,
+        base: &str,
+        date: NaiveDate,
+    ) -> Result<Snapshot<ConversionRate>> {
+        match self {
+            Provider::ExchangeRatesApi(provider) => {
+                provider.get_historical_conversion_rates(base, date).await
+            }
+            Provider::ECB(provider) => provider.get_historical_conversion_rates(base, date).await,
+        }
+    }
+}
@@ -1,70 +1,60 @@
-use std::collections::HashMap;
-
-use anyhow::Result;
-use rust_decimal::Decimal;
-use serde::Deserialize;
+use crate::error::Result;
+use chrono::NaiveDate;
 
 use crate::common::conversion_rate::ConversionRate;
-use crate::storage::common::StorageManager;
+use crate::storage::common::HistoryStorageManager;
 
-use super::common::ErrorResponseAPI;
+use super::provider::RateProvider;
 
 /// Update conversion rate files
-pub async fn update_conversion_rates<T>(
-    latest_endpoint_url: &str,
-    api_key: &str,
+pub async fn update_conversion_rates<P, T>(
+    provider: &P,
     base: &str,
     conversion_rates_storage_manager: &T,
 ) -> Result<()>
 where
-    T: StorageManager<ConversionRate>,
+    P: RateProvider,
+    T: HistoryStorageManager<ConversionRate>,
 {
-    let url = latest_endpoint_url
-        .replace("{api_key}", api_key)
-        .replace("{base}", base);
-    let data = get_conversion_rates(&url, base).await?;
+    let snapshot = provider.get_latest_conversion_rates(base).await?;
+
+    tracing::info!(
+        "{} conversion rates updated (effective date : {})",
+        snapshot.data.len(),
+        snapshot.date
+    );
 
-    conversion_rates_storage_manager.update(&data).await?;
+    conversion_rates_storage_manager
+        .update_snapshot(&snapshot)
+        .await?;
 
     Ok(())
 }
 
-/// Exchange rates API success response on latest endpoint
-#[derive(Deserialize, Debug)]
-struct SuccessLatestResponseAPI {
-    //success: bool,
-    //success: bool,
-    //timestamp: i64,
-    //base: String,
-    //date: String,
-    rates: HashMap<String, Decimal>,
-}
+/// Update conversion rates effective at a past `date` (other dates are kept)
+pub async fn update_historical_conversion_rates<P, T>(
+    provider: &P,
+    base: &str,
+    date: NaiveDate,
+    conversion_rates_storage_manager: &T,
+) -> Result<()>
+where
+    P: RateProvider,
+    T: HistoryStorageManager<ConversionRate>,
+{
+    let snapshot = provider.get_historical_conversion_rates(base, date).await?;
 
-/// Exchange rates API response on latest endpoint
-#[derive(Deserialize, Debug)]
-#[serde(untagged)]
-enum LatestResponseAPI {
-    Success(SuccessLatestResponseAPI),
-    Fail(ErrorResponseAPI),
-}
+    tracing::info!(
+        "{} conversion rates updated (effective date : {})",
+        snapshot.data.len(),
+        snapshot.date
+    );
 
-/// Get conversion rates for the base currency to others currencies from exchanges rates API
-async fn get_conversion_rates(url: &str, base: &str) -> Result<Vec<ConversionRate>> {
-    let response = reqwest::get(url).await?;
-
-    tracing::debug!("{:?}", response);
-
-    match response.json().await? {
-        LatestResponseAPI::Success(s) => Ok(crate::common::conversion_rate::from_hash_map_to_vec(
-            s.rates, base,
-        )?),
-        LatestResponseAPI::Fail(f) => Err(anyhow::anyhow!(
-            "Call {} failed : {} - {}",
-            url,
-            f.error.code,
-            f.error.message
-        )),
-    }
+    conversion_rates_storage_manager
+        .update_snapshot(&snapshot)
+        .await?;
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -72,10 +62,14 @@ mod test {
     use std::path::{Path, PathBuf};
 
     use httpmock::{Method::GET, MockServer};
-    use rust_decimal_macros::dec;
     use serde_json::json;
 
-    use crate::{common::conversion_rate::ConversionRate, storage::tsv::TSVStorageManager};
+    use chrono::NaiveDate;
+
+    use crate::{
+        storage::{common::HistoryStorageManager, tsv::TSVStorageManager},
+        update::exchange_rates_api::ExchangeRatesApiProvider,
+    };
 
     fn setup(path: &str) {
         std::fs::create_dir_all(path).unwrap();
@@ -86,7 +80,7 @@ mod test {
     }
 
     #[tokio::test]
-    async fn get_conversion_rates() {
+    async fn update_conversion_rates() {
         // param
         let api_key = "123";
         let base = "EUR";
@@ -101,17 +95,15 @@ mod test {
                 "USD": 0.813399,
             }
         });
-        let expected_usd = ConversionRate {
-            from: base.to_string(),
-            to: "USD".to_string(),
-            rate: dec!(0.813399),
-        };
+        let dirpath = "./temp/test/commands/update/update_conversion/";
+
+        setup(dirpath);
 
         let server = MockServer::start();
 
         let mock = server.mock(|when, then| {
             when.method(GET)
-                .path("/test")
+                .path("/latest")
                 .query_param("access_key", api_key)
                 .query_param("base", base);
             then.status(200)
@@ -119,80 +111,46 @@ mod test {
                 .json_body(server_response);
         });
 
-        let response = super::get_conversion_rates(
-            &server.url(format!("/test?access_key={api_key}&base={base}")),
-            base,
-        ).await;
-
-        mock.assert();
-
-        assert!(response.is_ok());
-        assert!(response.unwrap().contains(&expected_usd));
-    }
-
-    #[tokio::test]
-    async fn get_conversion_rates_fail() {
-        // param
-        let api_key = "123";
-        let base = "EUR";
-        let error_code = 104;
-        let error_info =
-            "Your monthly API request volume has been reached. Please upgrade your plan.";
-        let server_response = json!({
-          "success": false,
-          "error": {
-            "code": "104",
-            "message": "Your monthly API request volume has been reached. Please upgrade your plan."
-          }
-        });
-
-        let server = MockServer::start();
+        let mut file_path = PathBuf::new();
+        file_path.push(dirpath.to_string() + "conversion_rates.tsv");
 
-        let mock = server.mock(|when, then| {
-            when.method(GET)
-                .path("/test")
-                .query_param("access_key", api_key)
-                .query_param("base", base);
-            then.status(200)
-                .header("content-type", "application/json")
-                .json_body(server_response);
-        });
+        let storage_manager = TSVStorageManager::build(file_path.clone());
 
-        let expected_error_message = format!(
-            "Call {} failed : {} - {}",
-            server.url(format!("/test?access_key={api_key}&base={base}")),
-            error_code,
-            error_info
+        let provider = ExchangeRatesApiProvider::build(
+            api_key,
+            &server.url("/symbols?access_key={api_key}"),
+            &server.url("/latest?access_key={api_key}&base={base}"),
+            &server.url("/{date}?access_key={api_key}&base={base}"),
         );
 
-        let response = super::get_conversion_rates(
-            &server.url(format!("/test?access_key={api_key}&base={base}")),
-            base,
-        ).await;
+        let response = super::update_conversion_rates(&provider, base, &storage_manager).await;
 
         mock.assert();
 
-        assert!(response.is_err());
-        assert_eq!(response.unwrap_err().to_string(), expected_error_message);
+        println!("{:?}", response);
+        assert!(response.is_ok());
+
+        assert!(Path::new(&file_path).exists());
+
+        end(dirpath);
     }
 
     #[tokio::test]
-    async fn update_conversion_rates() {
+    async fn update_historical_conversion_rates() {
         // param
         let api_key = "123";
         let base = "EUR";
         let server_response = json!({
             "success": true,
-            "timestamp": 1519296206,
+            "historical": true,
+            "date": "2013-12-24",
+            "timestamp": 1387929599,
             "base": "EUR",
-            "date": "2021-03-17",
             "rates": {
-                "GBP": 0.72007,
-                "JPY": 107.346001,
-                "USD": 0.813399,
+                "USD": 1.367894,
             }
         });
-        let dirpath = "./temp/test/commands/update/update_conversion/";
+        let dirpath = "./temp/test/commands/update/update_historical_conversion/";
 
         setup(dirpath);
 
@@ -200,7 +158,7 @@ mod test {
 
         let mock = server.mock(|when, then| {
             when.method(GET)
-                .path("/latest")
+                .path("/2013-12-24")
                 .query_param("access_key", api_key)
                 .query_param("base", base);
             then.status(200)
@@ -213,19 +171,25 @@ mod test {
 
         let storage_manager = TSVStorageManager::build(file_path.clone());
 
-        let response = super::update_conversion_rates(
-            &server.url("/latest?access_key={api_key}&base={base}"),
+        let provider = ExchangeRatesApiProvider::build(
             api_key,
-            base,
-            &storage_manager,
-        ).await;
+            &server.url("/symbols?access_key={api_key}"),
+            &server.url("/latest?access_key={api_key}&base={base}"),
+            &server.url("/{date}?access_key={api_key}&base={base}"),
+        );
+
+        let date = NaiveDate::from_ymd_opt(2013, 12, 24).unwrap();
+        let response =
+            super::update_historical_conversion_rates(&provider, base, date, &storage_manager)
+                .await;
 
         mock.assert();
 
-        println!("{:?}", response);
         assert!(response.is_ok());
 
-        assert!(Path::new(&file_path).exists());
+        let snapshot = storage_manager.get_snapshot(date).await.unwrap();
+        assert!(snapshot.is_some());
+        assert_eq!(snapshot.unwrap().date, date);
 
         end(dirpath);
     }
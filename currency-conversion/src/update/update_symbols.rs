@@ -1,24 +1,17 @@
-use std::collections::HashMap;
-
 use crate::common::supported_symbols::Symbols;
 use crate::storage::common::StorageManager;
 
-use anyhow::Result;
-use serde::Deserialize;
+use crate::error::Result;
 
-use super::common::ErrorResponseAPI;
+use super::provider::RateProvider;
 
 /// Update supported symbols file
-pub async fn update_symbols<T>(
-    symbols_endpoint_url: &str,
-    api_key: &str,
-    symbols_storage_manager: &T,
-) -> Result<()>
+pub async fn update_symbols<P, T>(provider: &P, symbols_storage_manager: &T) -> Result<()>
 where
+    P: RateProvider,
     T: StorageManager<Symbols>,
 {
-    let url = symbols_endpoint_url.replace("{api_key}", api_key);
-    let symbols = get_supported_symbols(&url).await?;
+    let symbols = provider.get_symbols().await?;
 
     tracing::debug!("{:?}", &symbols);
     tracing::info!("{} Symbols updated", symbols.len());
@@ -28,38 +21,6 @@ where
     Ok(())
 }
 
-/// Exchange rates API success response on symbols endpoint
-#[derive(Deserialize, Debug)]
-struct SuccessSymbolResponseAPI {
-    //success: bool,
-    symbols: HashMap<String, String>,
-}
-
-/// Exchange rates API response on symbols endpoint
-#[derive(Deserialize, Debug)]
-#[serde(untagged)]
-enum SymbolResponseAPI {
-    Success(SuccessSymbolResponseAPI),
-    Fail(ErrorResponseAPI),
-}
-
-/// Get supported symbols from exchanges rates API
-async fn get_supported_symbols(url: &str) -> Result<Vec<Symbols>> {
-    let response = reqwest::get(url).await?;
-
-    match response.json().await? {
-        SymbolResponseAPI::Success(s) => Ok(
-            crate::common::supported_symbols::from_hash_map_to_vec(s.symbols)?,
-        ),
-        SymbolResponseAPI::Fail(f) => Err(anyhow::anyhow!(
-            "Call {} failed : {} - {}",
-            url,
-            f.error.code,
-            f.error.message
-        )),
-    }
-}
-
 #[cfg(test)]
 mod test {
     use std::path::{Path, PathBuf};
@@ -67,7 +28,9 @@ mod test {
     use httpmock::{Method::GET, MockServer};
     use serde_json::json;
 
-    use crate::{common::supported_symbols::Symbols, storage::tsv::TSVStorageManager};
+    use crate::{
+        storage::tsv::TSVStorageManager, update::exchange_rates_api::ExchangeRatesApiProvider,
+    };
 
     fn setup(path: &str) {
         std::fs::create_dir_all(path).unwrap();
@@ -77,90 +40,6 @@ mod test {
         std::fs::remove_dir_all(path).unwrap();
     }
 
-    #[tokio::test]
-    async fn get_supported_symbols() {
-        // param
-        let api_key = "123";
-
-        let server_response = json!(
-        {
-          "success": true,
-          "symbols": {
-            "AED": "United Arab Emirates Dirham",
-            "AFN": "Afghan Afghani",
-            "ALL": "Albanian Lek",
-            "AMD": "Armenian Dram",
-            }
-        }
-        );
-        let expected_all = Symbols {
-            code: "ALL".to_string(),
-            name: "Albanian Lek".to_string(),
-        };
-
-        let server = MockServer::start();
-
-        let mock = server.mock(|when, then| {
-            when.method(GET)
-                .path("/symbols")
-                .query_param("access_key", api_key);
-            then.status(200)
-                .header("content-type", "application/json")
-                .json_body(server_response);
-        });
-
-        let response =
-            super::get_supported_symbols(&server.url(format!("/symbols?access_key={api_key}"))).await;
-
-        mock.assert();
-
-        assert!(response.is_ok());
-        assert!(response.unwrap().contains(&expected_all));
-    }
-
-    
-    #[tokio::test]
-    async fn get_supported_symbols_fail() {
-        // param
-        let api_key = "123";
-        let error_code = 104;
-        let error_info =
-            "Your monthly API request volume has been reached. Please upgrade your plan.";
-        let server_response = json!({
-          "success": false,
-          "error": {
-            "code": "104",
-            "message": "Your monthly API request volume has been reached. Please upgrade your plan."
-          }
-        });
-
-        let server = MockServer::start();
-
-        let mock = server.mock(|when, then| {
-            when.method(GET)
-                .path("/test")
-                .query_param("access_key", api_key);
-            then.status(200)
-                .header("content-type", "application/json")
-                .json_body(server_response);
-        });
-
-        let expected_error_message = format!(
-            "Call {} failed : {} - {}",
-            server.url(format!("/test?access_key={api_key}")),
-            error_code,
-            error_info
-        );
-
-        let response =
-            super::get_supported_symbols(&server.url(format!("/test?access_key={api_key}"))).await;
-
-        mock.assert();
-
-        assert!(response.is_err());
-        assert_eq!(response.unwrap_err().to_string(), expected_error_message);
-    }
-
     #[tokio::test]
     async fn update_symbols() {
         // param
@@ -197,11 +76,14 @@ mod test {
 
         let storage_manager = TSVStorageManager::build(file_path.clone());
 
-        let response = super::update_symbols(
-            &server.url("/test?access_key={api_key}"),
+        let provider = ExchangeRatesApiProvider::build(
             api_key,
-            &storage_manager,
-        ).await;
+            &server.url("/test?access_key={api_key}"),
+            &server.url("/latest?access_key={api_key}&base={base}"),
+            &server.url("/{date}?access_key={api_key}&base={base}"),
+        );
+
+        let response = super::update_symbols(&provider, &storage_manager).await;
 
         mock.assert();
 
@@ -1,9 +1,15 @@
 //! Cli Arguments Parsing
 
-use clap::{Args, Parser, Subcommand};
+use std::{path::PathBuf, time::Duration};
+
+use chrono::NaiveDate;
+use clap::{ArgGroup, Args, Parser, Subcommand};
 use clap_verbosity_flag::Verbosity;
+use currency_conversion::{common::money_format::MoneyFormat, convert::rounding::RoundingMode};
 use rust_decimal::Decimal;
 
+use crate::output::OutputFormat;
+
 /// Handle currency conversion using local saved conversion rates
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -21,6 +27,10 @@ pub struct CliArgs {
     /// Optional : config profile name
     #[arg(long)]
     pub config_profile: Option<String>,
+
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
 }
 
 #[derive(Subcommand, Debug)]
@@ -33,20 +43,81 @@ pub enum SubCommand {
     List(ListArgs),
     /// Show informations
     Info(InfoArgs),
-    /// Prompt config
-    Config,
+    /// Prompt config (or show and edit it with a sub command)
+    Config(ConfigArgs),
+    /// Apply pending database migrations to the storages
+    Migrate,
+    /// Copy stored datasets between the storages of two configs (e.g. from TSV files to PSQL) without calling the provider
+    MigrateStorage(MigrateStorageArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub action: Option<ConfigAction>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Print the value of a key (nested keys are separated by ".", e.g. conversion_rates_storage.PSQL.host)
+    Get { key: String },
+    /// Set the value of a key (parsed as TOML, e.g. 5432 or '{ file_path = "rates.tsv" }', text otherwise)
+    Set { key: String, value: String },
+    /// Remove a key (its default value is used if it has one)
+    Unset { key: String },
+    /// Print the whole config
+    Show,
+    /// Check endpoints urls and storages
+    Validate,
 }
 
 #[derive(Args, Debug)]
 pub struct ConvertArgs {
     /// origin currency
+    #[arg(long, required_unless_present = "from_column")]
+    pub from: Option<String>,
+    /// destination currencies (repeated or comma separated)
+    #[arg(long, value_delimiter = ',', required_unless_present = "all")]
+    pub to: Vec<String>,
+    /// convert to all supported symbols
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["to", "input"])]
+    pub all: bool,
+    /// value to convert
+    #[arg(required_unless_present = "input", conflicts_with = "input")]
+    pub value: Option<Decimal>,
+    /// use conversion rates of a past day (YYYY-MM-DD), the nearest earlier stored rates are used if this day isn't stored
     #[arg(long)]
-    pub from: String,
-    /// destination currency
+    pub date: Option<NaiveDate>,
+    /// fetch conversion rates of `date` from provider if they aren't stored
+    #[arg(long, action = clap::ArgAction::SetTrue, requires = "date")]
+    pub fetch_missing: bool,
+    /// maximum age of conversion rates (e.g. "2days", "12h"), override `max_rate_age` of config
+    #[arg(long, value_parser = humantime::parse_duration, conflicts_with = "date")]
+    pub max_age: Option<Duration>,
+    /// rounding of converted values : half-even, half-up, truncate or none
+    #[arg(long, default_value = "half-even")]
+    pub round: RoundingMode,
+    /// number of decimals of converted values (default : minor units of the destination currency)
     #[arg(long)]
-    pub to: String,
-    /// value to convert
-    pub value: Decimal,
+    pub precision: Option<u32>,
+    /// writing of converted values : plain, symbol ($1,234.56), code (USD 1,234.56) or locale:<tag> (e.g. locale:fr-FR)
+    #[arg(long, default_value = "plain")]
+    pub format: MoneyFormat,
+    /// batch mode : csv or tsv file of values to convert ("-" to read stdin)
+    #[arg(long)]
+    pub input: Option<PathBuf>,
+    /// batch mode : output file (default : stdout), the global `--output` format doesn't apply to converted rows
+    #[arg(long, requires = "input")]
+    pub output_file: Option<PathBuf>,
+    /// batch mode : column of values to convert
+    #[arg(long, default_value = "amount", requires = "input")]
+    pub amount_column: String,
+    /// batch mode : column of origin currencies (instead of `from`)
+    #[arg(long, requires = "input", conflicts_with = "from")]
+    pub from_column: Option<String>,
+    /// batch mode : delimiter of input and output (default : tab for .tsv input, comma otherwise)
+    #[arg(long, requires = "input")]
+    pub delimiter: Option<char>,
 }
 
 #[derive(Args, Debug)]
@@ -81,6 +152,34 @@ pub struct InfoArgs {
     pub conversion_rates: bool,
 }
 
+#[derive(Debug, Args)]
+#[command(group(
+    ArgGroup::new("datasets")
+        .required(true)
+        .multiple(true)
+        .args(["all", "symbols", "conversion_rates"])
+))]
+pub struct MigrateStorageArgs {
+    /// Copy all datasets
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub all: bool,
+    /// Copy symbols
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub symbols: bool,
+    /// Copy conversion rates (with their history)
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub conversion_rates: bool,
+    /// path to the config file of the source storages (default : current config)
+    #[arg(long, required_unless_present = "to_config")]
+    pub from_config: Option<PathBuf>,
+    /// path to the config file of the destination storages (default : current config)
+    #[arg(long)]
+    pub to_config: Option<PathBuf>,
+    /// read and count data without writing them
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub dry_run: bool,
+}
+
 #[derive(Debug, Args)]
 pub struct UpdateArgs {
     /// Update all
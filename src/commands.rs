@@ -2,4 +2,6 @@ pub mod config;
 pub mod convert;
 pub mod info;
 pub mod list;
+pub mod migrate;
+pub mod migrate_storage;
 pub mod update;
@@ -1,28 +1,67 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-use crate::config::Config;
+use crate::{
+    cli::{ConfigAction, ConfigArgs},
+    config::Config,
+    errors::InvalidConfigError,
+    output::{render, CommandOutput, OutputFormat},
+};
+
+/// Config is written as its TOML file in text format
+impl CommandOutput for Config {
+    fn to_text(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?.trim_end().to_string())
+    }
+}
 
 #[cfg(not(tarpaulin_include))]
-pub fn run_config(
+pub async fn run_config(
     config: &Config,
+    args: &ConfigArgs,
     config_path: &Option<String>,
     config_profile: Option<&str>,
+    output: OutputFormat,
 ) -> Result<()> {
-    prompt_and_store_config(config, config_path, config_profile)?;
+    match &args.action {
+        None => {
+            prompt_and_store_config(config, config_path, config_profile).await?;
+        }
+        Some(ConfigAction::Get { key }) => {
+            println!("{}", format_value(&config.redacted().get_value(key)?)?);
+        }
+        Some(ConfigAction::Set { key, value }) => {
+            let res = config.set_value(key, value)?;
+            store_config(&res, config_path, config_profile)?;
+            tracing::info!("{key} set");
+        }
+        Some(ConfigAction::Unset { key }) => {
+            let res = config.unset_value(key)?;
+            store_config(&res, config_path, config_profile)?;
+            tracing::info!("{key} unset");
+        }
+        Some(ConfigAction::Show) => println!("{}", render(&config.redacted(), output)?),
+        Some(ConfigAction::Validate) => {
+            let problems = config.validate().await;
+            if !problems.is_empty() {
+                for problem in &problems {
+                    println!("{problem}");
+                }
+                return Err(anyhow!(InvalidConfigError { problems }));
+            }
+            println!("Config is valid");
+        }
+    }
     Ok(())
 }
 
 #[cfg(not(tarpaulin_include))]
-pub fn prompt_and_store_config(
+pub async fn prompt_and_store_config(
     config: &Config,
     config_path: &Option<String>,
     config_profile: Option<&str>,
 ) -> Result<Config> {
-    let res = Config::prompt_config(config)?;
-    match config_path.is_none() {
-        true => confy::store("currency-conversion-cli", config_profile, &res)?,
-        false => confy::store_path(config_path.clone().unwrap(), &res)?,
-    };
+    let res = Config::prompt_config(config).await?;
+    store_config(&res, config_path, config_profile)?;
 
     println!("Config Initialized !");
     tracing::info!("Config Initialized !");
@@ -30,3 +69,27 @@ pub fn prompt_and_store_config(
 
     Ok(res)
 }
+
+/// Store config in the config file selected (path or profile)
+#[cfg(not(tarpaulin_include))]
+fn store_config(
+    config: &Config,
+    config_path: &Option<String>,
+    config_profile: Option<&str>,
+) -> Result<()> {
+    match config_path {
+        None => confy::store("currency-conversion-cli", config_profile, config)?,
+        Some(path) => confy::store_path(path, config)?,
+    };
+
+    Ok(())
+}
+
+/// Format a config value : text as is, tables as TOML
+fn format_value(value: &toml::Value) -> Result<String> {
+    Ok(match value {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Table(table) => toml::to_string_pretty(table)?.trim_end().to_string(),
+        other => other.to_string(),
+    })
+}
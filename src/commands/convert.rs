@@ -1,49 +1,420 @@
-use crate::{cli::ConvertArgs, config::Config};
+use crate::{
+    cli::ConvertArgs,
+    config::Config,
+    output::{render, CommandOutput, OutputFormat},
+};
 
-use anyhow::Result;
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+};
 
-use currency_conversion::convert::convert_currency::convert;
+use anyhow::{anyhow, bail, Result};
 
-#[cfg(not(tarpaulin_include))]
-pub async fn run_convert(config: &Config, args: &ConvertArgs) -> Result<()> {
-    use anyhow::bail;
-    use currency_conversion::storage::{
-        common::StorageType, psql::PSQLStorageManager, tsv::TSVStorageManager,
-    };
-    use rust_decimal::Decimal;
+use chrono::NaiveDate;
+use currency_conversion::{
+    common::{
+        conversion_rate::ConversionRate,
+        money_format::{format_money, MoneyFormat},
+        rate_table::RateTable,
+        supported_symbols::Symbols,
+    },
+    convert::{
+        convert_batch::{convert_batch, BatchFrom, BatchSettings},
+        convert_currency::{
+            check_conversion_rates_age, convert, convert_at_date, convert_to_many,
+            get_conversion_rates_at_date, RatesFreshness,
+        },
+        rounding::{Rounding, RoundingMode},
+    },
+    list::list_data::ListDataItem,
+    storage::{
+        common::{HistoryStorageManager, StorageManager},
+        factory::AnyStorageManager,
+    },
+    update::update_converison_rates::update_historical_conversion_rates,
+};
+use rust_decimal::Decimal;
+use serde::Serialize;
 
+#[cfg(not(tarpaulin_include))]
+pub async fn run_convert(config: &Config, args: &ConvertArgs, output: OutputFormat) -> Result<()> {
     tracing::info!("Convert begin");
     tracing::debug!("{:?}", args);
 
-    let res: Decimal;
-    if let StorageType::TSV(settings) = config.conversion_rates_storage.clone() {
-        let storage_manager = TSVStorageManager::from_settings(settings)?;
-        res = convert(
-            &storage_manager,
-            &config.base,
-            &args.from,
-            &args.to,
-            args.value,
-        )
-        .await?;
-    } else if let StorageType::PSQL(settings) = config.conversion_rates_storage.clone() {
-        let storage_manager = PSQLStorageManager::from_settings(settings).await?;
-        res = convert(
-            &storage_manager,
+    // batch mode writes csv/tsv rows, the global output format doesn't apply
+    if args.input.is_some() && output != OutputFormat::Text {
+        bail!("--output can't be used with --input, converted rows are written as csv/tsv (use --output-file to write them to a file)");
+    }
+
+    let storage_manager =
+        AnyStorageManager::from_settings(config.conversion_rates_storage.clone()).await?;
+    let res = convert_with_storage(config, args, &storage_manager, output).await?;
+
+    if let Some(res) = res {
+        println!("{res}");
+        tracing::debug!("{res}");
+    }
+    tracing::info!("Convert end");
+    Ok(())
+}
+
+/// Result of a conversion
+#[derive(Serialize, Debug)]
+struct ConversionOutput {
+    from: String,
+    to: String,
+    value: Decimal,
+    /// value written with `--format` (only if it isn't plain)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formatted: Option<String>,
+    /// effective date of conversion rates (only with `--date`)
+    date: Option<NaiveDate>,
+    /// conversion rates path used (e.g. "USD -> EUR -> JPY")
+    path: String,
+}
+
+impl ConversionOutput {
+    /// Build the output of a converted `value` rounded with `rounding`
+    fn build(
+        from: &str,
+        to: String,
+        value: Decimal,
+        date: Option<NaiveDate>,
+        path: &[String],
+        rounding: &Rounding,
+        format: &MoneyFormat,
+    ) -> Result<ConversionOutput> {
+        let value = rounding.round(value, &to);
+        // minor units of the currency unless `--precision` or `--round none` is given
+        let decimals = match rounding {
+            Rounding {
+                precision: Some(precision),
+                ..
+            } => Some(*precision),
+            Rounding {
+                mode: RoundingMode::None,
+                ..
+            } => Some(value.scale()),
+            _ => None,
+        };
+        let formatted = match format {
+            MoneyFormat::Plain => None,
+            _ => Some(format_money(value, &to, decimals, format)?),
+        };
+
+        Ok(ConversionOutput {
+            from: from.to_string(),
+            to,
+            value,
+            formatted,
+            date,
+            path: path.join(" -> "),
+        })
+    }
+
+    /// Value to display (formatted one if any)
+    fn display_value(&self) -> String {
+        self.formatted
+            .clone()
+            .unwrap_or_else(|| self.value.to_string())
+    }
+}
+
+impl CommandOutput for ConversionOutput {
+    fn to_text(&self) -> Result<String> {
+        Ok(match self.date {
+            Some(date) => format!("{} (conversion rates of {date})", self.display_value()),
+            None => self.display_value(),
+        })
+    }
+}
+
+impl ListDataItem for ConversionOutput {
+    fn display_item(&self) -> String {
+        match self.date {
+            Some(date) => format!(
+                "{} : {} (conversion rates of {date})",
+                self.to,
+                self.display_value()
+            ),
+            None => format!("{} : {}", self.to, self.display_value()),
+        }
+    }
+}
+
+/// Convert with latest conversion rates or conversion rates of `args.date` and return the output to display (none in batch mode)
+#[cfg(not(tarpaulin_include))]
+async fn convert_with_storage<T>(
+    config: &Config,
+    args: &ConvertArgs,
+    storage_manager: &T,
+    output: OutputFormat,
+) -> Result<Option<String>>
+where
+    T: HistoryStorageManager<ConversionRate>,
+{
+    match args.date {
+        Some(date) if args.fetch_missing => {
+            fetch_missing_conversion_rates(config, date, storage_manager).await?;
+        }
+        // rates of a past day are expected to be old
+        Some(_) => (),
+        None => {
+            if let Some(max_age) = args.max_age.or(config.max_rate_age) {
+                let freshness = check_conversion_rates_age(
+                    storage_manager,
+                    max_age,
+                    config.stale_rates_policy,
+                    &config.get_provider(),
+                    &config.base,
+                )
+                .await?;
+
+                if let RatesFreshness::Stale(stale) = freshness {
+                    eprintln!("WARNING : {stale}");
+                }
+            }
+        }
+    }
+
+    if let Some(input) = &args.input {
+        run_convert_batch(config, args, input, storage_manager).await?;
+        return Ok(None);
+    }
+
+    // value and from are required without input (checked by clap)
+    let value = args.value.ok_or(anyhow!("No value to convert !"))?;
+    let from = args
+        .from
+        .as_deref()
+        .ok_or(anyhow!("No origin currency !"))?;
+
+    if args.all || args.to.len() > 1 {
+        return Ok(Some(
+            run_convert_to_many(config, args, from, value, storage_manager, output).await?,
+        ));
+    }
+
+    let to = args
+        .to
+        .first()
+        .ok_or(anyhow!("No destination currency !"))?;
+
+    let rounding = get_rounding(args);
+    let res = match args.date {
+        None => {
+            let res = convert(storage_manager, &config.base, from, to, value).await?;
+            ConversionOutput::build(
+                from,
+                to.clone(),
+                res.value,
+                None,
+                &res.path,
+                &rounding,
+                &args.format,
+            )?
+        }
+        Some(date) => {
+            let res = convert_at_date(storage_manager, &config.base, from, to, value, date).await?;
+            ConversionOutput::build(
+                from,
+                to.clone(),
+                res.value,
+                Some(res.date),
+                &res.path,
+                &rounding,
+                &args.format,
+            )?
+        }
+    };
+    tracing::info!("Conversion path : {}", res.path);
+
+    Ok(Some(render(&res, output)?))
+}
+
+/// Convert `value` to each currency of `args.to` (or all supported symbols) and return the output to display (one line by currency)
+#[cfg(not(tarpaulin_include))]
+async fn run_convert_to_many<T>(
+    config: &Config,
+    args: &ConvertArgs,
+    from: &str,
+    value: Decimal,
+    storage_manager: &T,
+    output: OutputFormat,
+) -> Result<String>
+where
+    T: HistoryStorageManager<ConversionRate>,
+{
+    let (rate_table, date) = get_rate_table(&config.base, args.date, storage_manager).await?;
+
+    let to = if args.all {
+        get_all_symbols_codes(config)
+            .await?
+            .into_iter()
+            .filter(|code| code != from)
+            .collect()
+    } else {
+        args.to.clone()
+    };
+
+    let conversions = convert_to_many(&rate_table, from, &to, value);
+    let rounding = get_rounding(args);
+
+    let mut res = Vec::new();
+    let mut errors = 0;
+    for conversion in conversions {
+        match conversion.value {
+            Ok(conversion_value) => res.push(ConversionOutput::build(
+                from,
+                conversion.to.clone(),
+                conversion_value.value,
+                date,
+                &conversion_value.path,
+                &rounding,
+                &args.format,
+            )?),
+            Err(e) => {
+                errors += 1;
+                tracing::error!("{} : {e}", conversion.to);
+            }
+        }
+    }
+
+    if errors > 0 {
+        // display successful conversions before failing
+        println!("{}", render(&res, output)?);
+        bail!("{errors} conversions failed !");
+    }
+
+    render(&res, output)
+}
+
+/// Get codes of all symbols stored
+#[cfg(not(tarpaulin_include))]
+async fn get_all_symbols_codes(config: &Config) -> Result<Vec<String>> {
+    let storage_manager = AnyStorageManager::from_settings(config.symbols_storage.clone()).await?;
+    let symbols: Vec<Symbols> = storage_manager.get_all().await?;
+
+    Ok(symbols.into_iter().map(|symbol| symbol.code).collect())
+}
+
+/// Rounding of converted values selected in args
+fn get_rounding(args: &ConvertArgs) -> Rounding {
+    Rounding {
+        mode: args.round,
+        precision: args.precision,
+    }
+}
+
+/// Get table of latest conversion rates or conversion rates as of `date` with their effective date
+#[cfg(not(tarpaulin_include))]
+async fn get_rate_table<T>(
+    base: &str,
+    date: Option<NaiveDate>,
+    storage_manager: &T,
+) -> Result<(RateTable, Option<NaiveDate>)>
+where
+    T: HistoryStorageManager<ConversionRate>,
+{
+    match date {
+        Some(date) => {
+            let snapshot = get_conversion_rates_at_date(storage_manager, date).await?;
+            Ok((RateTable::build(base, &snapshot.data), Some(snapshot.date)))
+        }
+        None => Ok((RateTable::from_storage(storage_manager, base).await?, None)),
+    }
+}
+
+/// Fetch conversion rates of `date` from provider if they aren't stored
+#[cfg(not(tarpaulin_include))]
+async fn fetch_missing_conversion_rates<T>(
+    config: &Config,
+    date: NaiveDate,
+    storage_manager: &T,
+) -> Result<()>
+where
+    T: HistoryStorageManager<ConversionRate>,
+{
+    let stored = storage_manager.get_snapshot(date).await?;
+    if stored.is_none_or(|snapshot| snapshot.date != date) {
+        tracing::info!("Fetch conversion rates of {date}");
+        update_historical_conversion_rates(
+            &config.get_provider(),
             &config.base,
-            &args.from,
-            &args.to,
-            args.value,
+            date,
+            storage_manager,
         )
         .await?;
     }
-    else{
-        bail!("No storage manager found !")
 
+    Ok(())
+}
+
+/// Convert all rows of `input` (conversion rates are loaded once)
+#[cfg(not(tarpaulin_include))]
+async fn run_convert_batch<T>(
+    config: &Config,
+    args: &ConvertArgs,
+    input: &Path,
+    storage_manager: &T,
+) -> Result<()>
+where
+    T: HistoryStorageManager<ConversionRate>,
+{
+    let [to] = args.to.as_slice() else {
+        bail!("Batch mode converts to one currency only !");
+    };
+
+    let (rate_table, _) = get_rate_table(&config.base, args.date, storage_manager).await?;
+
+    let from = match (&args.from_column, &args.from) {
+        (Some(column), _) => BatchFrom::Column(column.clone()),
+        (None, Some(currency)) => BatchFrom::Currency(currency.clone()),
+        (None, None) => bail!("No origin currency or origin currency column !"),
+    };
+
+    let delimiter = match args.delimiter {
+        Some(delimiter) if delimiter.is_ascii() => delimiter as u8,
+        Some(delimiter) => bail!("Delimiter \"{delimiter}\" isn't an ASCII character !"),
+        None if input.extension().is_some_and(|ext| ext == "tsv") => b'\t',
+        None => b',',
+    };
+
+    let settings = BatchSettings {
+        amount_column: args.amount_column.clone(),
+        from,
+        to: to.clone(),
+        delimiter,
+        rounding: get_rounding(args),
+    };
+
+    let reader: Box<dyn Read> = if input == Path::new("-") {
+        Box::new(std::io::stdin().lock())
+    } else {
+        Box::new(File::open(input)?)
+    };
+
+    let writer: Box<dyn Write> = match &args.output_file {
+        Some(output) => Box::new(File::create(output)?),
+        None => Box::new(std::io::stdout().lock()),
+    };
+
+    let report = convert_batch(&rate_table, &settings, reader, writer)?;
+
+    tracing::info!(
+        "{} rows converted, {} rows in error",
+        report.converted,
+        report.errors
+    );
+
+    if report.errors > 0 {
+        bail!(
+            "{} rows couldn't be converted (see error column)",
+            report.errors
+        );
     }
 
-    println!("{res}");
-    tracing::info!("Convert end");
-    tracing::debug!("{res}");
     Ok(())
 }
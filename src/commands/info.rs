@@ -1,80 +1,79 @@
-use std::collections::HashMap;
+use anyhow::{Context, Result};
+use currency_conversion::{
+    common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+    error::Error,
+    storage::{common::StorageManager, factory::AnyStorageManager},
+};
 
-use anyhow::Result;
+use crate::{
+    cli::InfoArgs,
+    config::Config,
+    output::{render, OutputFormat},
+};
 
-use crate::{cli::InfoArgs, config::Config};
-
-use self::{common::Info, info_config::get_config_info};
+use self::{
+    common::{DatasetInfo, Infos},
+    info_config::get_config_info,
+};
 
 mod common;
 mod info_config;
 
 #[cfg(not(tarpaulin_include))]
-pub async fn run_info(config: Config, args: &InfoArgs, config_path: Option<String>) -> Result<()> {
-    use currency_conversion::{
-        common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
-        storage::{
-            common::{StorageManager, StorageType},
-            psql::PSQLStorageManager,
-            tsv::TSVStorageManager,
-        },
-    };
-
-    let mut infos: HashMap<&str, Info> = HashMap::new();
+pub async fn run_info(
+    config: Config,
+    args: &InfoArgs,
+    config_path: Option<String>,
+    config_profile: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let mut infos = Infos::default();
 
     tracing::info!("Info begin");
     tracing::debug!("{:?}", args);
 
     // Symbols
     if args.symbols || args.all {
-        if let StorageType::TSV(settings) = config.symbols_storage.clone() {
-            let storage_manager = TSVStorageManager::from_settings(settings)?;
-            infos.insert(
-                "symbols",
-                Info::Symbols(StorageManager::<Symbols>::get_data_info(&storage_manager).await?),
-            );
-        } else if let StorageType::PSQL(settings) = config.symbols_storage.clone() {
-            let storage_manager = PSQLStorageManager::from_settings(settings).await?;
-            infos.insert(
-                "symbols",
-                Info::Symbols(StorageManager::<Symbols>::get_data_info(&storage_manager).await?),
-            );
-        }
+        let storage_manager =
+            AnyStorageManager::from_settings(config.symbols_storage.clone()).await?;
+        let data_info = StorageManager::<Symbols>::get_data_info(&storage_manager).await?;
+        let mut info = DatasetInfo::build(&config.symbols_storage, data_info, None);
+        info.schema_version = storage_manager.schema_version().await?;
+        infos.symbols = Some(info);
     }
 
     // Conversion rate
-
     if args.conversion_rates || args.all {
-        if let StorageType::TSV(settings) = config.symbols_storage.clone() {
-            let storage_manager = TSVStorageManager::from_settings(settings)?;
-            infos.insert(
-                "conversion_rates",
-                Info::ConversionRates(
-                    StorageManager::<ConversionRate>::get_data_info(&storage_manager).await?,
-                ),
-            );
-        } else if let StorageType::PSQL(settings) = config.symbols_storage.clone() {
-            let storage_manager = PSQLStorageManager::from_settings(settings).await?;
-            infos.insert(
-                "conversion_rates",
-                Info::ConversionRates(
-                    StorageManager::<ConversionRate>::get_data_info(&storage_manager).await?,
-                ),
-            );
-        }
+        let storage_manager =
+            AnyStorageManager::from_settings(config.conversion_rates_storage.clone()).await?;
+        let data_info = StorageManager::<ConversionRate>::get_data_info(&storage_manager).await?;
+        let mut info = DatasetInfo::build(
+            &config.conversion_rates_storage,
+            data_info,
+            config.max_rate_age,
+        );
+        info.schema_version = storage_manager.schema_version().await?;
+        infos.conversion_rates = Some(info);
     }
 
+    let max_rate_age = config.max_rate_age;
+
     // Config
     if args.config || args.all {
-        infos.insert(
-            "config",
-            Info::Config(get_config_info(config, config_path)?),
-        );
+        infos.config = Some(get_config_info(config, config_path, config_profile)?);
     }
 
-    println!("{:?}", infos);
+    println!("{}", render(&infos, output)?);
     tracing::info!("Info end");
     tracing::debug!("{:?}", infos);
 
+    // Stale conversion rates are reported with a distinct exit code
+    if let (Some(info), Some(max_age)) = (&infos.conversion_rates, max_rate_age) {
+        info.data
+            .check_age(max_age)
+            .map_err(Error::StaleData)
+            .context("Conversion rates must be updated")?;
+    }
+
     Ok(())
 }
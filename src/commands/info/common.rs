@@ -1,11 +1,216 @@
-use currency_conversion::storage::common::DataInfo;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use currency_conversion::storage::common::{DataInfo, StorageType};
+use serde::Serialize;
+
+use crate::output::{to_fields, CommandOutput};
 
 use super::info_config::ConfigInfo;
 
-/// All type of information
-#[derive(Debug)]
-pub enum Info {
-    Config(ConfigInfo),
-    Symbols(DataInfo),
-    ConversionRates(DataInfo),
+/// All type of information (only requested ones are filled)
+#[derive(Serialize, Debug, Default)]
+pub struct Infos {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbols: Option<DatasetInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversion_rates: Option<DatasetInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<ConfigInfo>,
+}
+
+/// Information about a dataset and its storage
+#[derive(Serialize, Debug)]
+pub struct DatasetInfo {
+    /// storage backend (TSV, PSQL, SQLITE)
+    pub storage: String,
+    /// file path or database of the storage
+    pub location: String,
+    #[serde(flatten)]
+    pub data: DataInfo,
+    /// date of the last update (only if data are available)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_update: Option<DateTime<Utc>>,
+    /// maximum age allowed (only if one is configured)
+    #[serde(with = "humantime_serde", skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<Duration>,
+    /// data are older than `max_age`
+    pub stale: bool,
+    /// version of the last migration applied (only for databases)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<i64>,
+}
+
+impl DatasetInfo {
+    /// Build dataset information from its storage and data info, data are checked against `max_age` if defined
+    pub fn build(storage: &StorageType, data: DataInfo, max_age: Option<Duration>) -> DatasetInfo {
+        let (storage, location) = match storage {
+            StorageType::TSV(settings) => ("TSV", settings.file_path.display().to_string()),
+            StorageType::PSQL(settings) => (
+                "PSQL",
+                format!(
+                    "{}:{}/{}",
+                    settings.host, settings.port, settings.database_name
+                ),
+            ),
+            StorageType::SQLITE(settings) => ("SQLITE", settings.file_path.display().to_string()),
+        };
+
+        let last_update = match &data {
+            DataInfo::Success(info) => chrono::Duration::from_std(info.seconds_since_last_update)
+                .ok()
+                .map(|age| Utc::now() - age),
+            DataInfo::Error(_) => None,
+        };
+
+        let stale = max_age.is_some_and(|max_age| data.check_age(max_age).is_err());
+
+        DatasetInfo {
+            storage: storage.to_string(),
+            location,
+            data,
+            last_update,
+            max_age,
+            stale,
+            schema_version: None,
+        }
+    }
+
+    /// Indented report lines
+    fn to_text(&self) -> String {
+        let mut lines = vec![
+            format!("  storage : {}", self.storage),
+            format!("  location : {}", self.location),
+        ];
+
+        if let Some(version) = self.schema_version {
+            lines.push(format!("  schema version : {version}"));
+        }
+
+        match &self.data {
+            DataInfo::Success(info) => {
+                lines.push(format!("  rows : {}", info.number_of_line));
+                lines.push(format!(
+                    "  last update : {} ({})",
+                    format_relative_time(info.seconds_since_last_update),
+                    self.last_update
+                        .map(|date| date.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                        .unwrap_or_default()
+                ));
+            }
+            DataInfo::Error(info) => lines.push(format!("  error : {:#}", info.error)),
+        }
+
+        if let (true, Some(max_age)) = (self.stale, self.max_age) {
+            lines.push(format!(
+                "  WARNING : data are older than max_rate_age ({})",
+                humantime::format_duration(max_age)
+            ));
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl CommandOutput for Infos {
+    fn to_text(&self) -> Result<String> {
+        let mut sections = Vec::new();
+
+        if let Some(info) = &self.symbols {
+            sections.push(format!("Symbols\n{}", info.to_text()));
+        }
+
+        if let Some(info) = &self.conversion_rates {
+            sections.push(format!("Conversion rates\n{}", info.to_text()));
+        }
+
+        if let Some(info) = &self.config {
+            let content = to_fields(&info.content)?
+                .iter()
+                .map(|(key, value)| match info.sources.get(key) {
+                    Some(source) => format!("  {key} : {value} ({source})"),
+                    None => format!("  {key} : {value}"),
+                })
+                .collect::<Vec<String>>()
+                .join("\n");
+            sections.push(format!("Config\n  path : {}\n{content}", info.config_path));
+        }
+
+        Ok(sections.join("\n\n"))
+    }
+}
+
+/// Format a duration as a relative time (e.g. "3 days ago")
+pub fn format_relative_time(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+
+    let (value, unit) = match seconds {
+        0..60 => return "just now".to_string(),
+        60..3600 => (seconds / 60, "minute"),
+        3600..86400 => (seconds / 3600, "hour"),
+        _ => (seconds / 86400, "day"),
+    };
+
+    match value {
+        1 => format!("1 {unit} ago"),
+        _ => format!("{value} {unit}s ago"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use currency_conversion::storage::{
+        common::{DataInfo, DataInfoSuccess, StorageType},
+        tsv::TSVStorageSettings,
+    };
+
+    use super::DatasetInfo;
+
+    #[test]
+    fn format_relative_time() {
+        assert_eq!(
+            super::format_relative_time(Duration::from_secs(5)),
+            "just now"
+        );
+        assert_eq!(
+            super::format_relative_time(Duration::from_secs(60)),
+            "1 minute ago"
+        );
+        assert_eq!(
+            super::format_relative_time(Duration::from_secs(7300)),
+            "2 hours ago"
+        );
+        assert_eq!(
+            super::format_relative_time(Duration::from_secs(3 * 86400 + 10)),
+            "3 days ago"
+        );
+    }
+
+    #[test]
+    fn dataset_info_stale() {
+        let storage = StorageType::TSV(TSVStorageSettings {
+            file_path: "conversion_rates.tsv".into(),
+        });
+        let data = || {
+            DataInfo::Success(DataInfoSuccess {
+                seconds_since_last_update: Duration::from_secs(3 * 86400),
+                number_of_line: 170,
+            })
+        };
+
+        let info = DatasetInfo::build(&storage, data(), Some(Duration::from_secs(86400)));
+
+        assert!(info.stale);
+        assert_eq!(info.storage, "TSV");
+        assert_eq!(info.location, "conversion_rates.tsv");
+        assert!(info.to_text().contains("WARNING"));
+
+        let info = DatasetInfo::build(&storage, data(), None);
+
+        assert!(!info.stale);
+        assert!(info.to_text().contains("3 days ago"));
+    }
 }
@@ -1,21 +1,30 @@
+use std::collections::BTreeMap;
+
 use anyhow::Result;
 use serde::Serialize;
 
-use crate::config::Config;
+use crate::{config::Config, output::to_fields};
 
 /// Information about configuration of this app
 #[derive(Serialize, Debug)]
 pub struct ConfigInfo {
-    config_path: String,
-    content: Config,
+    pub config_path: String,
+    pub content: Config,
+    /// origin of each (dotted) key of content : "file", "env (<variable>)" or "default"
+    pub sources: BTreeMap<String, String>,
 }
 
-/// Get informations about configuration of this app
-pub fn get_config_info(config: Config, config_path: Option<String>) -> Result<ConfigInfo> {
+/// Get informations about configuration of this app (file of `config_profile` without `config_path`)
+pub fn get_config_info(
+    config: Config,
+    config_path: Option<String>,
+    config_profile: Option<&str>,
+) -> Result<ConfigInfo> {
     let current_config_path = match config_path {
         Some(path) => path,
         None => {
-            let path = confy::get_configuration_file_path("currency-conversion-cli", None)?;
+            let path =
+                confy::get_configuration_file_path("currency-conversion-cli", config_profile)?;
 
             let path = path.into_os_string().into_string();
 
@@ -27,12 +36,45 @@ pub fn get_config_info(config: Config, config_path: Option<String>) -> Result<Co
         }
     };
 
+    let sources = get_sources(&config, &current_config_path)?;
+
     Ok(ConfigInfo {
         config_path: current_config_path,
-        content: config,
+        content: config.redacted(),
+        sources,
     })
 }
 
+/// Get origin of each key of config (environment variable, config file or default value)
+fn get_sources(config: &Config, config_path: &str) -> Result<BTreeMap<String, String>> {
+    // keys missing from the file (or unreadable file) get their default values
+    let file_keys: Vec<String> = match std::fs::read_to_string(config_path)
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+    {
+        Some(table) => to_fields(&table)?.into_iter().map(|(key, _)| key).collect(),
+        None => Vec::new(),
+    };
+
+    Ok(to_fields(config)?
+        .into_iter()
+        .map(|(key, _)| {
+            let env_override = config
+                .env_overrides
+                .iter()
+                .find(|o| key == o.key || key.starts_with(&format!("{}.", o.key)));
+
+            let source = match env_override {
+                Some(o) => format!("env ({})", o.variable),
+                None if file_keys.contains(&key) => "file".to_string(),
+                None => "default".to_string(),
+            };
+
+            (key, source)
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod test {
     use crate::config::Config;
@@ -49,10 +91,41 @@ mod test {
 
         confy::store_path(path.clone(), &config).unwrap();
 
-        let res = super::get_config_info(config, Some(path.clone()));
+        let res = super::get_config_info(config, Some(path.clone()), None);
+
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        assert_eq!(res.config_path, path);
+        assert_eq!(res.sources["base"], "file");
+
+        std::fs::remove_dir_all(dirpath).unwrap();
+    }
+
+    #[test]
+    fn get_config_info_sources() {
+        let dirpath = "./temp/test/info/info_config_sources/";
+
+        std::fs::create_dir_all(dirpath).unwrap();
+
+        let path = dirpath.to_string() + "config.toml";
+
+        std::fs::write(
+            &path,
+            "base = \"EUR\"\nsymbols_endpoint_url = \"http://localhost/symbols\"\n",
+        )
+        .unwrap();
+
+        let config = Config::default()
+            .with_env_overrides(vec![("CCC_API_KEY".to_string(), "123".to_string())])
+            .unwrap();
+
+        let res = super::get_config_info(config, Some(path.clone()), None);
 
         assert!(res.is_ok());
-        assert_eq!(res.unwrap().config_path, path);
+        let sources = res.unwrap().sources;
+        assert_eq!(sources["base"], "file");
+        assert_eq!(sources["api_key"], "env (CCC_API_KEY)");
+        assert_eq!(sources["latest_endpoint_url"], "default");
 
         std::fs::remove_dir_all(dirpath).unwrap();
     }
@@ -63,8 +136,27 @@ mod test {
 
         confy::store("convert_currency_cli", "test", &config).unwrap();
 
-        let res = super::get_config_info(config, None);
+        let res = super::get_config_info(config, None, None);
+
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn get_config_info_profile() {
+        let profile = "test_info_config_profile";
+        let path =
+            confy::get_configuration_file_path("currency-conversion-cli", Some(profile)).unwrap();
+
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "base = \"USD\"\n").unwrap();
+
+        let res = super::get_config_info(Config::default(), None, Some(profile));
 
         assert!(res.is_ok());
+        let res = res.unwrap();
+        assert_eq!(res.config_path, path.to_string_lossy());
+        assert_eq!(res.sources["base"], "file");
+
+        std::fs::remove_file(path).unwrap();
     }
 }
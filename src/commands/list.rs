@@ -1,48 +1,40 @@
 use anyhow::Result;
 use currency_conversion::common::conversion_rate::ConversionRate;
 use currency_conversion::common::supported_symbols::Symbols;
-use currency_conversion::list::list_data::list_data;
 use currency_conversion::list::list_data::ListDataItem;
 use currency_conversion::storage::common::StorageManager;
+use currency_conversion::storage::factory::AnyStorageManager;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::{
     cli::{ListArgs, ListDataSet},
     config::Config,
+    output::{render, OutputFormat},
 };
 
 #[cfg(not(tarpaulin_include))]
-pub async fn run_list(config: &Config, args: &ListArgs) -> Result<()> {
-    use currency_conversion::storage::{
-        common::StorageType, psql::PSQLStorageManager, tsv::TSVStorageManager,
-    };
-
+pub async fn run_list(config: &Config, args: &ListArgs, output: OutputFormat) -> Result<()> {
     match args.dataset {
         ListDataSet::Symbols => {
-            if let StorageType::TSV(settings) = &config.symbols_storage {
-                let storage_manager = TSVStorageManager::from_settings(settings.clone())?;
-                load_and_list_data::<Symbols>(storage_manager).await?;
-            } else if let StorageType::PSQL(settings) = &config.symbols_storage {
-                let storage_manager = PSQLStorageManager::from_settings(settings.clone()).await?;
-                load_and_list_data::<Symbols>(storage_manager).await?;
-            }
+            let storage_manager =
+                AnyStorageManager::from_settings(config.symbols_storage.clone()).await?;
+            load_and_list_data::<Symbols>(storage_manager, output).await?;
         }
         ListDataSet::ConversionRates => {
-            if let StorageType::TSV(settings) = &config.symbols_storage {
-                let storage_manager = TSVStorageManager::from_settings(settings.clone())?;
-                load_and_list_data::<ConversionRate>(storage_manager).await?;
-            } else if let StorageType::PSQL(settings) = &config.symbols_storage {
-                let storage_manager = PSQLStorageManager::from_settings(settings.clone()).await?;
-                load_and_list_data::<ConversionRate>(storage_manager).await?;
-            }
+            let storage_manager =
+                AnyStorageManager::from_settings(config.conversion_rates_storage.clone()).await?;
+            load_and_list_data::<ConversionRate>(storage_manager, output).await?;
         }
     };
     Ok(())
 }
 
 #[cfg(not(tarpaulin_include))]
-async fn load_and_list_data<T>(storage_manager: impl StorageManager<T>) -> Result<()>
+async fn load_and_list_data<T>(
+    storage_manager: impl StorageManager<T>,
+    output: OutputFormat,
+) -> Result<()>
 where
     T: ListDataItem + for<'de> Deserialize<'de> + Ord + Serialize,
 {
@@ -50,7 +42,7 @@ where
 
     data.sort();
 
-    println!("{}", list_data(&data)?);
+    println!("{}", render(&data, output)?);
 
     Ok(())
 }
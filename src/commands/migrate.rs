@@ -0,0 +1,75 @@
+use anyhow::{Context, Result};
+use currency_conversion::{
+    list::list_data::ListDataItem,
+    storage::{common::StorageType, factory::AnyStorageManager},
+};
+use serde::Serialize;
+
+use crate::{
+    config::Config,
+    output::{render, OutputFormat},
+};
+
+/// Schema of a dataset storage after migration
+#[derive(Serialize, Debug)]
+struct MigrationOutput {
+    dataset: String,
+    storage: String,
+    /// version of the last migration applied (none for files)
+    schema_version: Option<i64>,
+}
+
+impl ListDataItem for MigrationOutput {
+    fn display_item(&self) -> String {
+        match self.schema_version {
+            Some(version) => format!(
+                "{} : {} schema version {version}",
+                self.dataset, self.storage
+            ),
+            None => format!("{} : {} (no schema)", self.dataset, self.storage),
+        }
+    }
+}
+
+/// Apply pending migrations to the databases of symbols and conversion rates storages
+#[cfg(not(tarpaulin_include))]
+pub async fn run_migrate(config: &Config, output: OutputFormat) -> Result<()> {
+    tracing::info!("Migrate begin");
+
+    let mut res = Vec::new();
+    for (dataset, storage) in [
+        ("symbols", &config.symbols_storage),
+        ("conversion_rates", &config.conversion_rates_storage),
+    ] {
+        res.push(migrate_storage(dataset, storage).await?);
+    }
+
+    println!("{}", render(&res, output)?);
+    tracing::info!("Migrate end");
+    Ok(())
+}
+
+/// Apply pending migrations to a storage and return its schema version
+#[cfg(not(tarpaulin_include))]
+async fn migrate_storage(dataset: &str, storage: &StorageType) -> Result<MigrationOutput> {
+    let storage_manager = AnyStorageManager::from_settings(storage.clone()).await?;
+    storage_manager
+        .migrate()
+        .await
+        .with_context(|| format!("Migration of {dataset} storage failed"))?;
+
+    Ok(MigrationOutput {
+        dataset: dataset.to_string(),
+        storage: storage_name(storage).to_string(),
+        schema_version: storage_manager.schema_version().await?,
+    })
+}
+
+/// Name of the storage type (as written in config)
+pub(crate) fn storage_name(storage: &StorageType) -> &'static str {
+    match storage {
+        StorageType::TSV(_) => "TSV",
+        StorageType::PSQL(_) => "PSQL",
+        StorageType::SQLITE(_) => "SQLITE",
+    }
+}
@@ -0,0 +1,170 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use currency_conversion::{
+    common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+    list::list_data::ListDataItem,
+    storage::{
+        common::StorageType,
+        copy::{copy_data, copy_history, CopyCount},
+        factory::AnyStorageManager,
+    },
+};
+use serde::Serialize;
+
+use crate::{
+    cli::MigrateStorageArgs,
+    config::Config,
+    output::{render, OutputFormat},
+};
+
+use super::migrate::storage_name;
+
+/// Dataset copied from a storage to an other
+#[derive(Debug, Clone, Copy)]
+enum Dataset {
+    Symbols,
+    /// conversion rates with their history
+    ConversionRates,
+}
+
+impl Dataset {
+    fn name(&self) -> &'static str {
+        match self {
+            Dataset::Symbols => "symbols",
+            Dataset::ConversionRates => "conversion_rates",
+        }
+    }
+}
+
+/// Result of a dataset copy
+#[derive(Serialize, Debug)]
+struct StorageCopyOutput {
+    dataset: String,
+    from: String,
+    to: String,
+    /// source and destination are the same storage (nothing copied)
+    skipped: bool,
+    /// data were only read and counted
+    dry_run: bool,
+    snapshots: usize,
+    items: usize,
+}
+
+impl ListDataItem for StorageCopyOutput {
+    fn display_item(&self) -> String {
+        if self.skipped {
+            return format!("{} : skipped (same storage)", self.dataset);
+        }
+
+        let copied = match self.dry_run {
+            true => "to copy",
+            false => "copied",
+        };
+        format!(
+            "{} : {} snapshot(s) ({} items) {copied} from {} to {}",
+            self.dataset, self.snapshots, self.items, self.from, self.to
+        )
+    }
+}
+
+/// Copy datasets from the storages of a config to the storages of an other config
+#[cfg(not(tarpaulin_include))]
+pub async fn run_migrate_storage(
+    config: &Config,
+    args: &MigrateStorageArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    tracing::debug!("Migrate storage arguments : {:?}", args);
+    tracing::info!("Migrate storage begin");
+
+    let source = match &args.from_config {
+        Some(path) => load_config(path)?,
+        None => config.clone(),
+    };
+    let destination = match &args.to_config {
+        Some(path) => load_config(path)?,
+        None => config.clone(),
+    };
+
+    let mut res = Vec::new();
+    if args.all || args.symbols {
+        res.push(
+            copy_dataset(
+                Dataset::Symbols,
+                &source.symbols_storage,
+                &destination.symbols_storage,
+                args.dry_run,
+            )
+            .await?,
+        );
+    }
+    if args.all || args.conversion_rates {
+        res.push(
+            copy_dataset(
+                Dataset::ConversionRates,
+                &source.conversion_rates_storage,
+                &destination.conversion_rates_storage,
+                args.dry_run,
+            )
+            .await?,
+        );
+    }
+
+    println!("{}", render(&res, output)?);
+    tracing::info!("Migrate storage end");
+    Ok(())
+}
+
+/// Load a config file with its secrets resolved
+#[cfg(not(tarpaulin_include))]
+fn load_config(path: &Path) -> Result<Config> {
+    // confy creates missing files, a typo must not create an empty config
+    if !path.exists() {
+        bail!("Config file {} not found", path.display());
+    }
+
+    let mut config: Config = confy::load_path(path)
+        .with_context(|| format!("Config file {} can't be loaded", path.display()))?;
+    config.resolve_secrets()?;
+
+    Ok(config)
+}
+
+/// Copy a dataset from a storage to an other (history of conversion rates included)
+#[cfg(not(tarpaulin_include))]
+async fn copy_dataset(
+    dataset: Dataset,
+    from: &StorageType,
+    to: &StorageType,
+    dry_run: bool,
+) -> Result<StorageCopyOutput> {
+    let mut res = StorageCopyOutput {
+        dataset: dataset.name().to_string(),
+        from: storage_name(from).to_string(),
+        to: storage_name(to).to_string(),
+        skipped: from == to,
+        dry_run,
+        snapshots: 0,
+        items: 0,
+    };
+
+    if res.skipped {
+        return Ok(res);
+    }
+
+    let source = AnyStorageManager::from_settings(from.clone()).await?;
+    let destination = AnyStorageManager::from_settings(to.clone()).await?;
+
+    let CopyCount { snapshots, items } = match dataset {
+        Dataset::Symbols => copy_data::<Symbols>(&source, &destination, dry_run).await,
+        Dataset::ConversionRates => {
+            copy_history::<ConversionRate>(&source, &destination, dry_run).await
+        }
+    }
+    .with_context(|| format!("Copy of {} failed", dataset.name()))?;
+
+    res.snapshots = snapshots;
+    res.items = items;
+    Ok(res)
+}
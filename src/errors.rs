@@ -1,4 +1,5 @@
 use anyhow::Result;
+use currency_conversion::error::Error;
 
 /// Error handling function call in main
 #[cfg(not(tarpaulin_include))]
@@ -6,9 +7,48 @@ pub fn errors_handling(error: anyhow::Error) -> Result<()> {
     tracing::error!("{:#}", error);
 
     // Return different exitcode depending on error type
+    if let Some(error) = error.downcast_ref::<Error>() {
+        std::process::exit(get_exit_code(error));
+    }
+
     if error.downcast_ref::<std::io::Error>().is_some() {
         std::process::exit(exitcode::IOERR);
     }
 
+    if error.downcast_ref::<InvalidConfigError>().is_some() {
+        std::process::exit(exitcode::CONFIG);
+    }
+
     std::process::exit(1);
 }
+
+/// Exit code of a library error
+fn get_exit_code(error: &Error) -> exitcode::ExitCode {
+    match error {
+        Error::UnknownCurrency(_) | Error::InvalidConversionRate { .. } | Error::Parse(_) => {
+            exitcode::DATAERR
+        }
+        Error::NoConversionPath { .. } | Error::MissingConversionRates(_) => exitcode::NOINPUT,
+        Error::ProviderHttp(_) | Error::Database(_) => exitcode::UNAVAILABLE,
+        Error::ProviderApi { .. } | Error::Provider(_) => exitcode::PROTOCOL,
+        Error::StorageIo(_) | Error::StorageFile(_) | Error::CopyMismatch { .. } => exitcode::IOERR,
+        // Stale data are fixed by an update
+        Error::StaleData(_) => exitcode::TEMPFAIL,
+        Error::InvalidSettings(_) => exitcode::CONFIG,
+    }
+}
+
+/// Config content isn't valid
+#[derive(Debug)]
+pub struct InvalidConfigError {
+    /// problems found (one by key)
+    pub problems: Vec<String>,
+}
+
+impl std::fmt::Display for InvalidConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid config : {}", self.problems.join(", "))
+    }
+}
+
+impl std::error::Error for InvalidConfigError {}
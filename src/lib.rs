@@ -2,11 +2,13 @@ use anyhow::Result;
 use cli::SubCommand;
 use commands::update::run_update;
 use config::Config;
+use output::OutputFormat;
 
 pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod errors;
+pub mod output;
 
 /// Handle commands execution
 #[cfg(not(tarpaulin_include))]
@@ -15,17 +17,27 @@ pub async fn run(
     config: Config,
     config_path: Option<String>,
     config_profile: Option<&str>,
+    output: OutputFormat,
 ) -> Result<()> {
     use commands::convert::run_convert;
 
-    use crate::commands::{config::run_config, info::run_info, list::run_list};
+    use crate::commands::{
+        config::run_config, info::run_info, list::run_list, migrate::run_migrate,
+        migrate_storage::run_migrate_storage,
+    };
 
     match sub_command {
         SubCommand::Update(args) => run_update(&config, &args).await?,
-        SubCommand::Convert(args) => run_convert(&config, &args).await?,
-        SubCommand::List(args) => run_list(&config, &args).await?,
-        SubCommand::Info(args) => run_info(config, &args, config_path).await?,
-        SubCommand::Config => run_config(&config, &config_path, config_profile)?,
+        SubCommand::Convert(args) => run_convert(&config, &args, output).await?,
+        SubCommand::List(args) => run_list(&config, &args, output).await?,
+        SubCommand::Info(args) => {
+            run_info(config, &args, config_path, config_profile, output).await?
+        }
+        SubCommand::Config(args) => {
+            run_config(&config, &args, &config_path, config_profile, output).await?
+        }
+        SubCommand::Migrate => run_migrate(&config, output).await?,
+        SubCommand::MigrateStorage(args) => run_migrate_storage(&config, &args, output).await?,
     }
     Ok(())
 }
@@ -9,7 +9,10 @@ async fn main() -> Result<()> {
     // Get command line arguments
 
     use anyhow::Context;
-    use currency_conversion_cli::{cli::SubCommand, commands::config::prompt_and_store_config};
+    use currency_conversion_cli::{
+        cli::{ConfigAction, ConfigArgs, SubCommand},
+        commands::config::prompt_and_store_config,
+    };
     let args = CliArgs::parse();
 
     // Initialize trace
@@ -18,21 +21,50 @@ async fn main() -> Result<()> {
         .init();
 
     // Get config
-    let mut config: Config = match &args.config_path.is_none() {
+    let file_config: Config = match &args.config_path.is_none() {
         true => confy::load("currency-conversion-cli", args.config_profile.as_deref())
             .with_context(|| "Use \"currency-conversion-cli config\" to create the config")?,
         false => confy::load_path(args.config_path.clone().unwrap())
             .with_context(|| "Use \"currency-conversion-cli config\" to create the config")?,
     };
 
+    // Config stored by the command is built without environment overrides (they must not be stored)
+    let store_config = matches!(
+        &args.sub_command,
+        SubCommand::Config(ConfigArgs {
+            action: None | Some(ConfigAction::Set { .. }) | Some(ConfigAction::Unset { .. })
+        })
+    );
+    let mut config = match store_config {
+        true => file_config.clone(),
+        false => file_config.with_env_overrides(std::env::vars())?,
+    };
+
+    // Config sub commands (get, set, ...) work without initialization
+    let edit_config = matches!(
+        &args.sub_command,
+        SubCommand::Config(ConfigArgs { action: Some(_) })
+    );
+
     // Initialized config if not
-    if config.api_key == "#INSERT_API_KEY_HERE#" {
-        config =
-            prompt_and_store_config(&config, &args.config_path, args.config_profile.as_deref())?;
+    if config.needs_initialization() && !edit_config {
+        let stored_config = prompt_and_store_config(
+            &file_config,
+            &args.config_path,
+            args.config_profile.as_deref(),
+        )
+        .await?;
 
-        if let SubCommand::Config = args.sub_command {
+        if let SubCommand::Config(_) = args.sub_command {
             return Ok(());
         }
+
+        config = stored_config.with_env_overrides(std::env::vars())?;
+    }
+
+    // Secrets are only needed (and resolved) by commands using providers or storages
+    if !matches!(args.sub_command, SubCommand::Config(_)) {
+        config.resolve_secrets()?;
     }
 
     match run(
@@ -40,6 +72,7 @@ async fn main() -> Result<()> {
         config,
         args.config_path,
         args.config_profile.as_deref(),
+        args.output,
     )
     .await
     {
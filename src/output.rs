@@ -0,0 +1,293 @@
+//! Output formatting of commands results
+
+use anyhow::Result;
+use clap::ValueEnum;
+use currency_conversion::list::list_data::{list_data, ListDataItem};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Output formats available
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// human readable text
+    #[default]
+    Text,
+    /// JSON document
+    Json,
+    /// CSV with a header line
+    Csv,
+    /// aligned table
+    Table,
+}
+
+/// Result of a command which can be written in all output formats
+pub trait CommandOutput: Serialize {
+    /// Human readable text (default : one "field : value" line by field)
+    fn to_text(&self) -> Result<String> {
+        Ok(to_fields(self)?
+            .iter()
+            .map(|(key, value)| format!("{key} : {value}"))
+            .collect::<Vec<String>>()
+            .join("\n"))
+    }
+}
+
+/// Get all (flattened) fields of `data` as (dotted key, value) pairs
+pub fn to_fields<T: Serialize + ?Sized>(data: &T) -> Result<Vec<(String, String)>> {
+    let mut fields = Vec::new();
+    flatten(&serde_json::to_value(data)?, "", &mut fields);
+
+    Ok(fields)
+}
+
+/// List of items is written one item by line in text format
+impl<T> CommandOutput for Vec<T>
+where
+    T: ListDataItem + Serialize,
+{
+    fn to_text(&self) -> Result<String> {
+        Ok(list_data(self)?.trim_end().to_string())
+    }
+}
+
+/// Render `data` in `format`
+pub fn render<T: CommandOutput>(data: &T, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Text => data.to_text(),
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(data)?),
+        OutputFormat::Csv => to_csv(&to_rows(&serde_json::to_value(data)?)),
+        OutputFormat::Table => Ok(to_table(&to_rows(&serde_json::to_value(data)?))),
+    }
+}
+
+/// Tabular view of a serialized value
+#[derive(Debug, PartialEq)]
+struct Rows {
+    headers: Vec<String>,
+    records: Vec<Vec<String>>,
+}
+
+/// Build tabular view of a serialized value :
+/// - an array gives one row by item with one column by (flattened) field
+/// - any other value gives one "field, value" row by (flattened) field
+fn to_rows(value: &Value) -> Rows {
+    match value {
+        Value::Array(items) => {
+            let items: Vec<Vec<(String, String)>> = items
+                .iter()
+                .map(|item| {
+                    let mut fields = Vec::new();
+                    flatten(item, "", &mut fields);
+                    fields
+                })
+                .collect();
+
+            let mut headers: Vec<String> = Vec::new();
+            for (key, _) in items.iter().flatten() {
+                if !headers.contains(key) {
+                    headers.push(key.clone());
+                }
+            }
+
+            let records = items
+                .iter()
+                .map(|fields| {
+                    headers
+                        .iter()
+                        .map(|header| {
+                            fields
+                                .iter()
+                                .find(|(key, _)| key == header)
+                                .map(|(_, value)| value.clone())
+                                .unwrap_or_default()
+                        })
+                        .collect()
+                })
+                .collect();
+
+            Rows { headers, records }
+        }
+        _ => {
+            let mut fields = Vec::new();
+            flatten(value, "", &mut fields);
+
+            Rows {
+                headers: vec!["field".to_string(), "value".to_string()],
+                records: fields
+                    .into_iter()
+                    .map(|(key, value)| vec![key, value])
+                    .collect(),
+            }
+        }
+    }
+}
+
+/// Flatten nested objects and arrays in (dotted key, value) pairs
+fn flatten(value: &Value, prefix: &str, fields: &mut Vec<(String, String)>) {
+    let key = |name: &str| match prefix {
+        "" => name.to_string(),
+        _ => format!("{prefix}.{name}"),
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (name, value) in map {
+                flatten(value, &key(name), fields);
+            }
+        }
+        Value::Array(items) => {
+            for (index, value) in items.iter().enumerate() {
+                flatten(value, &key(&index.to_string()), fields);
+            }
+        }
+        Value::String(s) => fields.push((prefix.to_string(), s.clone())),
+        Value::Null => fields.push((prefix.to_string(), String::new())),
+        _ => fields.push((prefix.to_string(), value.to_string())),
+    }
+}
+
+fn to_csv(rows: &Rows) -> Result<String> {
+    let mut wtr = csv::Writer::from_writer(vec![]);
+
+    wtr.write_record(&rows.headers)?;
+    for record in &rows.records {
+        wtr.write_record(record)?;
+    }
+
+    let res = String::from_utf8(wtr.into_inner()?)?;
+
+    Ok(res.trim_end().to_string())
+}
+
+fn to_table(rows: &Rows) -> String {
+    let widths: Vec<usize> = rows
+        .headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.records
+                .iter()
+                .map(|record| record[i].chars().count())
+                .chain(std::iter::once(header.chars().count()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<String>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let separator = widths
+        .iter()
+        .map(|width| "-".repeat(*width))
+        .collect::<Vec<String>>()
+        .join("-+-");
+
+    std::iter::once(line(&rows.headers))
+        .chain(std::iter::once(separator))
+        .chain(rows.records.iter().map(|record| line(record)))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use currency_conversion::list::list_data::ListDataItem;
+    use serde::Serialize;
+
+    use super::{CommandOutput, OutputFormat};
+
+    #[derive(Serialize)]
+    struct Item {
+        code: String,
+        rate: f64,
+    }
+
+    #[derive(Serialize)]
+    struct Report {
+        name: String,
+        info: Item,
+    }
+
+    impl ListDataItem for Item {
+        fn display_item(&self) -> String {
+            format!("{} : {}", self.code, self.rate)
+        }
+    }
+
+    impl CommandOutput for Report {}
+
+    fn items() -> Vec<Item> {
+        vec![
+            Item {
+                code: "USD".to_string(),
+                rate: 1.08,
+            },
+            Item {
+                code: "GBP".to_string(),
+                rate: 0.84,
+            },
+        ]
+    }
+
+    #[test]
+    fn render_csv() {
+        let res = super::render(&items(), OutputFormat::Csv);
+
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), "code,rate\nUSD,1.08\nGBP,0.84");
+    }
+
+    #[test]
+    fn render_table() {
+        let res = super::render(&items(), OutputFormat::Table);
+
+        assert!(res.is_ok());
+        assert_eq!(
+            res.unwrap(),
+            "code | rate\n-----+-----\nUSD  | 1.08\nGBP  | 0.84"
+        );
+    }
+
+    #[test]
+    fn render_nested() {
+        let report = Report {
+            name: "test".to_string(),
+            info: Item {
+                code: "EUR".to_string(),
+                rate: 1.0,
+            },
+        };
+
+        let res = super::render(&report, OutputFormat::Text);
+        assert!(res.is_ok());
+        assert_eq!(
+            res.unwrap(),
+            "name : test\ninfo.code : EUR\ninfo.rate : 1.0"
+        );
+
+        let res = super::render(&report, OutputFormat::Csv);
+        assert!(res.is_ok());
+        assert_eq!(
+            res.unwrap(),
+            "field,value\nname,test\ninfo.code,EUR\ninfo.rate,1.0"
+        );
+    }
+
+    #[test]
+    fn render_json() {
+        let res = super::render(&items(), OutputFormat::Json);
+
+        assert!(res.is_ok());
+        let value: serde_json::Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(value[1]["code"], "GBP");
+    }
+}
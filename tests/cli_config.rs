@@ -0,0 +1,158 @@
+use assert_cmd::Command;
+use currency_conversion::convert::convert_currency::StaleRatesPolicy;
+use currency_conversion_cli::config::Config;
+use predicates::prelude::predicate;
+
+#[test]
+fn cli_config() -> Result<(), Box<dyn std::error::Error>> {
+    let dirpath = "./temp/test/cli_config";
+    std::fs::create_dir_all(dirpath).unwrap();
+    let config_path = dirpath.to_string() + "/config.toml";
+
+    // set works on a new config without initialization
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("config")
+        .arg("set")
+        .arg("api_key")
+        .arg("123")
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("config")
+        .arg("set")
+        .arg("symbols_storage.TSV.file_path")
+        .arg(dirpath.to_string() + "/symbols.tsv")
+        .assert()
+        .success();
+
+    let config: Config = confy::load_path(&config_path)?;
+    assert_eq!(config.api_key.configured(), "123");
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("config")
+        .arg("get")
+        .arg("symbols_storage.TSV.file_path")
+        .assert()
+        .success()
+        .stdout(dirpath.to_string() + "/symbols.tsv\n");
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("config")
+        .arg("show")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("api_key = \"********\""));
+
+    // invalid value isn't stored
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("config")
+        .arg("set")
+        .arg("stale_rates_policy")
+        .arg("ignore")
+        .assert()
+        .failure()
+        .code(exitcode::CONFIG);
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("config")
+        .arg("set")
+        .arg("latest_endpoint_url")
+        .arg("http://localhost/latest")
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("config")
+        .arg("validate")
+        .assert()
+        .failure()
+        .code(exitcode::CONFIG)
+        .stdout(predicate::str::contains(
+            "latest_endpoint_url : missing {api_key} placeholder",
+        ));
+
+    std::fs::remove_dir_all(dirpath).unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn cli_config_env() -> Result<(), Box<dyn std::error::Error>> {
+    let dirpath = "./temp/test/cli_config_env";
+    std::fs::create_dir_all(dirpath).unwrap();
+    let config_path = dirpath.to_string() + "/config.toml";
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+    let output = cmd
+        .env("CCC_API_KEY", "123")
+        .env("CCC_BASE", "USD")
+        .arg("--config-path")
+        .arg(&config_path)
+        .arg("info")
+        .arg("--config")
+        .arg("--output")
+        .arg("json")
+        .output()?;
+
+    assert!(output.status.success());
+    let infos: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(infos["config"]["content"]["base"], "USD");
+    assert_eq!(infos["config"]["sources"]["base"], "env (CCC_BASE)");
+    assert_eq!(infos["config"]["sources"]["symbols_endpoint_url"], "file");
+
+    // environment overrides aren't stored
+    let config: Config = confy::load_path(&config_path)?;
+    assert_eq!(config.base, "EUR");
+
+    std::fs::remove_dir_all(dirpath).unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn cli_config_prompt_keep_settings() -> Result<(), Box<dyn std::error::Error>> {
+    let dirpath = "./temp/test/cli_config_prompt_keep_settings";
+    std::fs::create_dir_all(dirpath).unwrap();
+    let config_path = dirpath.to_string() + "/config.toml";
+
+    let config = Config {
+        api_key: "test".into(),
+        max_rate_age: Some(std::time::Duration::from_secs(2 * 24 * 3600)),
+        stale_rates_policy: StaleRatesPolicy::Error,
+        ..Default::default()
+    };
+    confy::store_path(&config_path, config.clone()).unwrap();
+
+    // all current values are kept
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("config")
+        .write_stdin("\n".repeat(20))
+        .assert()
+        .success();
+
+    // settings not prompted are kept too
+    let res: Config = confy::load_path(&config_path)?;
+    assert_eq!(res.max_rate_age, config.max_rate_age);
+    assert_eq!(res.stale_rates_policy, StaleRatesPolicy::Error);
+
+    std::fs::remove_dir_all(dirpath).unwrap();
+
+    Ok(())
+}
@@ -1,24 +1,44 @@
-use std::path::PathBuf;
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
 
 use assert_cmd::Command;
+use chrono::NaiveDate;
 use currency_conversion::{
-    common::conversion_rate::ConversionRate,
+    common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+    convert::convert_currency::StaleRatesPolicy,
     storage::{
-        common::StorageManager,
+        common::{HistoryStorageManager, Snapshot, StorageManager},
         tsv::{TSVStorageManager, TSVStorageSettings},
     },
 };
 use currency_conversion_cli::config::Config;
-use predicates::prelude::predicate;
+use httpmock::{Method::GET, MockServer};
+use predicates::prelude::{predicate, PredicateBooleanExt};
 use rust_decimal_macros::dec;
+use serde_json::json;
 
 #[tokio::test]
 async fn cli_convert() -> Result<(), Box<dyn std::error::Error>> {
-    let data = vec![ConversionRate {
-        from: "EUR".to_string(),
-        to: "USD".to_string(),
-        rate: dec!(1.08),
-    }];
+    let data = vec![
+        ConversionRate {
+            from: "EUR".to_string(),
+            to: "USD".to_string(),
+            rate: dec!(1.08),
+        },
+        // quoted against an other base
+        ConversionRate {
+            from: "USD".to_string(),
+            to: "JPY".to_string(),
+            rate: dec!(150),
+        },
+        ConversionRate {
+            from: "EUR".to_string(),
+            to: "KWD".to_string(),
+            rate: dec!(0.3312345),
+        },
+    ];
 
     let dirpath = "./temp/test/cli_convert";
 
@@ -39,7 +59,7 @@ async fn cli_convert() -> Result<(), Box<dyn std::error::Error>> {
             tsv_settings,
         ),
         base: "EUR".to_string(),
-        api_key: "test".to_string(),
+        api_key: "test".into(),
         ..Default::default()
     };
 
@@ -49,7 +69,7 @@ async fn cli_convert() -> Result<(), Box<dyn std::error::Error>> {
 
     cmd.arg("-vvv")
         .arg("--config-path")
-        .arg(config_path)
+        .arg(&config_path)
         .arg("convert")
         .arg("--from")
         .arg("EUR")
@@ -60,6 +80,600 @@ async fn cli_convert() -> Result<(), Box<dyn std::error::Error>> {
         .success()
         .stdout(predicate::str::contains("10.8"));
 
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .arg("10.009")
+        .arg("--round")
+        .arg("truncate")
+        .assert()
+        .success()
+        .stdout("10.80\n");
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .arg("10.009")
+        .arg("--precision")
+        .arg("3")
+        .assert()
+        .success()
+        .stdout("10.810\n");
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .arg("1234.5")
+        .arg("--format")
+        .arg("symbol")
+        .assert()
+        .success()
+        .stdout("$1,333.26\n");
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .arg("1234.5")
+        .arg("--format")
+        .arg("locale:de-DE")
+        .assert()
+        .success()
+        .stdout("1.333,26 $\n");
+
+    // rounding options apply to formatted values
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .arg("10.009")
+        .arg("--precision")
+        .arg("3")
+        .arg("--format")
+        .arg("symbol")
+        .assert()
+        .success()
+        .stdout("$10.810\n");
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .arg("10.009")
+        .arg("--round")
+        .arg("none")
+        .arg("--format")
+        .arg("code")
+        .assert()
+        .success()
+        .stdout("USD 10.80972\n");
+
+    // formatted with minor units of the destination currency
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("JPY")
+        .arg("1.0005")
+        .arg("--format")
+        .arg("symbol")
+        .assert()
+        .success()
+        .stdout("¥162\n");
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("KWD")
+        .arg("10")
+        .arg("--format")
+        .arg("code")
+        .assert()
+        .success()
+        .stdout("KWD 3.312\n");
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("XXX")
+        .arg("10")
+        .assert()
+        .failure()
+        .code(exitcode::DATAERR);
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    let output = cmd
+        .arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("JPY")
+        .arg("10")
+        .arg("--output")
+        .arg("json")
+        .output()?;
+
+    assert!(output.status.success());
+    let conversion: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    // rounded to JPY minor units
+    assert_eq!(conversion["value"], "1620");
+    assert_eq!(conversion["path"], "EUR -> USD -> JPY");
+
+    std::fs::remove_dir_all(dirpath).unwrap();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cli_convert_date() -> Result<(), Box<dyn std::error::Error>> {
+    let dirpath = "./temp/test/cli_convert_date";
+
+    std::fs::create_dir_all(dirpath).unwrap();
+
+    let mut path = PathBuf::new();
+
+    path.push(dirpath.to_string() + "/conversion_rate.tsv");
+
+    let tsv_settings = TSVStorageSettings { file_path: path };
+    let storage_manager = TSVStorageManager::from_settings(tsv_settings.clone()).unwrap();
+
+    storage_manager
+        .update_snapshot(&Snapshot {
+            date: NaiveDate::from_ymd_opt(2024, 9, 2).unwrap(),
+            data: vec![ConversionRate {
+                from: "EUR".to_string(),
+                to: "USD".to_string(),
+                rate: dec!(1.07),
+            }],
+        })
+        .await
+        .unwrap();
+
+    // mock historical endpoint
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/2024-09-04")
+            .query_param("access_key", "test")
+            .query_param("base", "EUR");
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(json!({
+                "success": true,
+                "historical": true,
+                "date": "2024-09-04",
+                "timestamp": 1725494399,
+                "base": "EUR",
+                "rates": {
+                    "USD": 1.09,
+                }
+            }));
+    });
+
+    let config_path = dirpath.to_string() + "/config.toml";
+    let config = Config {
+        conversion_rates_storage: currency_conversion::storage::common::StorageType::TSV(
+            tsv_settings,
+        ),
+        base: "EUR".to_string(),
+        api_key: "test".into(),
+        historical_endpoint_url: server.url("/{date}") + "?access_key={api_key}&base={base}",
+        ..Default::default()
+    };
+
+    confy::store_path(&config_path, config).unwrap();
+
+    // nearest earlier stored rates
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("-vvv")
+        .arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .arg("--date")
+        .arg("2024-09-04")
+        .arg("10")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("10.7"))
+        .stdout(predicate::str::contains("2024-09-02"));
+
+    // rates fetched from provider
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("-vvv")
+        .arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .arg("--date")
+        .arg("2024-09-04")
+        .arg("--fetch-missing")
+        .arg("10")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("10.9"))
+        .stdout(predicate::str::contains("2024-09-04"));
+
+    mock.assert();
+
+    std::fs::remove_dir_all(dirpath).unwrap();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cli_convert_batch() -> Result<(), Box<dyn std::error::Error>> {
+    let data = vec![
+        ConversionRate {
+            from: "EUR".to_string(),
+            to: "USD".to_string(),
+            rate: dec!(1.08),
+        },
+        ConversionRate {
+            from: "EUR".to_string(),
+            to: "TBH".to_string(),
+            rate: dec!(32),
+        },
+    ];
+
+    let dirpath = "./temp/test/cli_convert_batch";
+
+    std::fs::create_dir_all(dirpath).unwrap();
+
+    let mut path = PathBuf::new();
+
+    path.push(dirpath.to_string() + "/conversion_rate.tsv");
+
+    let tsv_settings = TSVStorageSettings { file_path: path };
+    let storage_manager = TSVStorageManager::from_settings(tsv_settings.clone()).unwrap();
+
+    storage_manager.update(&data).await.unwrap();
+
+    let config_path = dirpath.to_string() + "/config.toml";
+    let config = Config {
+        conversion_rates_storage: currency_conversion::storage::common::StorageType::TSV(
+            tsv_settings,
+        ),
+        base: "EUR".to_string(),
+        api_key: "test".into(),
+        ..Default::default()
+    };
+
+    confy::store_path(&config_path, config).unwrap();
+
+    // file to file, a row in error doesn't stop the conversion
+    let input_path = dirpath.to_string() + "/expenses.csv";
+    let output_path = dirpath.to_string() + "/out.csv";
+    std::fs::write(
+        &input_path,
+        "label,amount,currency\nhotel,64,TBH\nflight,50,XXX\ntaxi,10,USD\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("-vvv")
+        .arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--input")
+        .arg(&input_path)
+        .arg("--from-column")
+        .arg("currency")
+        .arg("--to")
+        .arg("EUR")
+        .arg("--output-file")
+        .arg(&output_path)
+        .assert()
+        .failure();
+
+    let mut rdr = csv::Reader::from_path(&output_path)?;
+    let rows: Vec<csv::StringRecord> = rdr.records().collect::<Result<_, _>>()?;
+
+    assert_eq!(rows.len(), 3);
+    assert_eq!(&rows[0][3], "2.00");
+    assert_eq!(&rows[0][4], "0.03125");
+    assert_eq!(&rows[1][3], "");
+    assert!(!rows[1][5].is_empty());
+    assert_eq!(&rows[2][3], "9.26");
+
+    // stdin to stdout
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--input")
+        .arg("-")
+        .arg("--delimiter")
+        .arg("\t")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .write_stdin("amount\n10\n")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "amount\tconverted_amount\tconversion_rate\terror\n10\t10.80\t1.08\t\n",
+        ));
+
+    // output format of results doesn't apply to batch mode
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("--output")
+        .arg("json")
+        .arg("convert")
+        .arg("--input")
+        .arg("-")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .write_stdin("amount\n10\n")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("--output-file"));
+
+    std::fs::remove_dir_all(dirpath).unwrap();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cli_convert_many() -> Result<(), Box<dyn std::error::Error>> {
+    let data = vec![
+        ConversionRate {
+            from: "EUR".to_string(),
+            to: "USD".to_string(),
+            rate: dec!(1.08),
+        },
+        ConversionRate {
+            from: "EUR".to_string(),
+            to: "GBP".to_string(),
+            rate: dec!(0.84),
+        },
+    ];
+    let symbols = vec![
+        Symbols {
+            code: "EUR".to_string(),
+            name: "Euro".to_string(),
+        },
+        Symbols {
+            code: "USD".to_string(),
+            name: "United States Dollar".to_string(),
+        },
+        Symbols {
+            code: "GBP".to_string(),
+            name: "British Pound Sterling".to_string(),
+        },
+    ];
+
+    let dirpath = "./temp/test/cli_convert_many";
+
+    std::fs::create_dir_all(dirpath).unwrap();
+
+    let rates_settings = TSVStorageSettings {
+        file_path: PathBuf::from(dirpath.to_string() + "/conversion_rate.tsv"),
+    };
+    TSVStorageManager::from_settings(rates_settings.clone())
+        .unwrap()
+        .update(&data)
+        .await
+        .unwrap();
+
+    let symbols_settings = TSVStorageSettings {
+        file_path: PathBuf::from(dirpath.to_string() + "/symbols.tsv"),
+    };
+    TSVStorageManager::from_settings(symbols_settings.clone())
+        .unwrap()
+        .update(&symbols)
+        .await
+        .unwrap();
+
+    let config_path = dirpath.to_string() + "/config.toml";
+    let config = Config {
+        conversion_rates_storage: currency_conversion::storage::common::StorageType::TSV(
+            rates_settings,
+        ),
+        symbols_storage: currency_conversion::storage::common::StorageType::TSV(symbols_settings),
+        base: "EUR".to_string(),
+        api_key: "test".into(),
+        ..Default::default()
+    };
+
+    confy::store_path(&config_path, config).unwrap();
+
+    // comma list and repeated --to
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD,GBP")
+        .arg("--to")
+        .arg("XXX")
+        .arg("100")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("USD : 108"))
+        .stdout(predicate::str::contains("GBP : 84"));
+
+    // all supported symbols
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("USD")
+        .arg("--all")
+        .arg("108")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("EUR : 100"))
+        .stdout(predicate::str::contains("GBP : 84"))
+        .stdout(predicate::str::contains("USD").not());
+
+    std::fs::remove_dir_all(dirpath).unwrap();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cli_convert_stale() -> Result<(), Box<dyn std::error::Error>> {
+    let data = vec![ConversionRate {
+        from: "EUR".to_string(),
+        to: "USD".to_string(),
+        rate: dec!(1.08),
+    }];
+
+    let dirpath = "./temp/test/cli_convert_stale";
+
+    std::fs::create_dir_all(dirpath).unwrap();
+
+    let path = PathBuf::from(dirpath.to_string() + "/conversion_rate.tsv");
+
+    let tsv_settings = TSVStorageSettings {
+        file_path: path.clone(),
+    };
+    let storage_manager = TSVStorageManager::from_settings(tsv_settings.clone()).unwrap();
+
+    storage_manager.update(&data).await.unwrap();
+
+    // last update 3 days ago
+    std::fs::File::options()
+        .write(true)
+        .open(&path)?
+        .set_modified(SystemTime::now() - Duration::from_secs(3 * 24 * 3600))?;
+
+    let config_path = dirpath.to_string() + "/config.toml";
+    let mut config = Config {
+        conversion_rates_storage: currency_conversion::storage::common::StorageType::TSV(
+            tsv_settings,
+        ),
+        base: "EUR".to_string(),
+        api_key: "test".into(),
+        ..Default::default()
+    };
+
+    confy::store_path(&config_path, &config).unwrap();
+
+    // default policy : warn
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .arg("--max-age")
+        .arg("1day")
+        .arg("10")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("10.8"))
+        .stderr(predicate::str::contains("WARNING"));
+
+    // error policy with max_rate_age from config
+    config.max_rate_age = Some(Duration::from_secs(24 * 3600));
+    config.stale_rates_policy = StaleRatesPolicy::Error;
+    confy::store_path(&config_path, &config).unwrap();
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .arg("10")
+        .assert()
+        .code(exitcode::TEMPFAIL);
+
+    // --max-age overrides config
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .arg("--max-age")
+        .arg("4days")
+        .arg("10")
+        .assert()
+        .success();
+
     std::fs::remove_dir_all(dirpath).unwrap();
 
     Ok(())
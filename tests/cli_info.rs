@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
 
 use assert_cmd::Command;
 use currency_conversion::{
@@ -9,6 +12,7 @@ use currency_conversion::{
     },
 };
 use currency_conversion_cli::config::Config;
+use predicates::prelude::predicate;
 use rust_decimal_macros::dec;
 
 #[tokio::test]
@@ -58,7 +62,7 @@ async fn cli_info() -> Result<(), Box<dyn std::error::Error>> {
         symbols_storage: currency_conversion::storage::common::StorageType::TSV(
             tsv_settings_symbols,
         ),
-        api_key: "test".to_string(),
+        api_key: "test".into(),
         ..Default::default()
     };
 
@@ -68,12 +72,91 @@ async fn cli_info() -> Result<(), Box<dyn std::error::Error>> {
 
     cmd.arg("-vvv")
         .arg("--config-path")
-        .arg(config_path)
+        .arg(&config_path)
         .arg("info")
         .arg("--all")
         .assert()
         .success();
 
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    let output = cmd
+        .arg("--config-path")
+        .arg(&config_path)
+        .arg("info")
+        .arg("--symbols")
+        .arg("--conversion-rates")
+        .arg("--output")
+        .arg("json")
+        .output()?;
+
+    assert!(output.status.success());
+    let infos: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(infos["symbols"]["status"], "success");
+    assert_eq!(infos["symbols"]["number_of_line"], 1);
+    assert!(infos["symbols"]["seconds_since_last_update"].is_u64());
+    assert_eq!(infos["conversion_rates"]["status"], "success");
+    assert_eq!(infos["conversion_rates"]["storage"], "TSV");
+    assert_eq!(infos["conversion_rates"]["stale"], false);
+    assert!(infos.get("config").is_none());
+
+    std::fs::remove_dir_all(dirpath).unwrap();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cli_info_stale() -> Result<(), Box<dyn std::error::Error>> {
+    let conversion_rates = vec![ConversionRate {
+        from: "EUR".to_string(),
+        to: "USD".to_string(),
+        rate: dec!(1.08),
+    }];
+
+    let dirpath = "./temp/test/cli_info_stale";
+
+    std::fs::create_dir_all(dirpath).unwrap();
+
+    let conversion_rates_path = PathBuf::from(dirpath.to_string() + "/conversion_rate.tsv");
+    let tsv_settings_conversion_rates = TSVStorageSettings {
+        file_path: conversion_rates_path.clone(),
+    };
+    let storage_manager_conversion_rates =
+        TSVStorageManager::from_settings(tsv_settings_conversion_rates.clone()).unwrap();
+
+    StorageManager::update(&storage_manager_conversion_rates, &conversion_rates)
+        .await
+        .unwrap();
+
+    // last update 3 days ago
+    std::fs::File::options()
+        .write(true)
+        .open(&conversion_rates_path)?
+        .set_modified(SystemTime::now() - Duration::from_secs(3 * 24 * 3600))?;
+
+    let config_path = dirpath.to_string() + "/config.toml";
+    let config = Config {
+        conversion_rates_storage: currency_conversion::storage::common::StorageType::TSV(
+            tsv_settings_conversion_rates,
+        ),
+        api_key: "test".into(),
+        max_rate_age: Some(Duration::from_secs(2 * 24 * 3600)),
+        ..Default::default()
+    };
+
+    confy::store_path(&config_path, config).unwrap();
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("info")
+        .arg("--conversion-rates")
+        .assert()
+        .code(exitcode::TEMPFAIL)
+        .stdout(predicate::str::contains("3 days ago"))
+        .stdout(predicate::str::contains("WARNING"));
+
     std::fs::remove_dir_all(dirpath).unwrap();
 
     Ok(())
@@ -39,7 +39,7 @@ async fn cli_get_symbols() -> Result<(), Box<dyn std::error::Error>> {
     let config_path = dirpath.to_string() + "/config.toml";
     let config = Config {
         symbols_storage: currency_conversion::storage::common::StorageType::TSV(tsv_settings),
-        api_key: "test".to_string(),
+        api_key: "test".into(),
         ..Default::default()
     };
 
@@ -49,13 +49,27 @@ async fn cli_get_symbols() -> Result<(), Box<dyn std::error::Error>> {
 
     cmd.arg("-vvv")
         .arg("--config-path")
-        .arg(config_path)
+        .arg(&config_path)
         .arg("list")
         .arg("symbols")
         .assert()
         .success()
         .stdout(predicate::str::contains("EUR : Euro"));
 
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("list")
+        .arg("symbols")
+        .arg("--output")
+        .arg("csv")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "code,name\nEUR,Euro\nUSD,United state dollard\n",
+        ));
+
     std::fs::remove_dir_all(dirpath).unwrap();
 
     Ok(())
@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use assert_cmd::Command;
+use currency_conversion::storage::{
+    common::StorageType, sqlite::SQLiteStorageSettings, sqlite::MIGRATOR, tsv::TSVStorageSettings,
+};
+use currency_conversion_cli::config::Config;
+use predicates::prelude::predicate;
+
+#[test]
+fn cli_migrate() -> Result<(), Box<dyn std::error::Error>> {
+    let dirpath = "./temp/test/cli_migrate";
+
+    std::fs::create_dir_all(dirpath).unwrap();
+
+    let config_path = dirpath.to_string() + "/config.toml";
+    let config = Config {
+        conversion_rates_storage: StorageType::SQLITE(SQLiteStorageSettings {
+            file_path: PathBuf::from(dirpath.to_string() + "/conversion_rates.db"),
+        }),
+        symbols_storage: StorageType::TSV(TSVStorageSettings {
+            file_path: PathBuf::from(dirpath.to_string() + "/symbols.tsv"),
+        }),
+        api_key: "test".into(),
+        ..Default::default()
+    };
+
+    confy::store_path(&config_path, config).unwrap();
+
+    let latest = MIGRATOR.iter().map(|m| m.version).max().unwrap();
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("migrate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("symbols : TSV (no schema)"))
+        .stdout(predicate::str::contains(format!(
+            "conversion_rates : SQLITE schema version {latest}"
+        )));
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("info")
+        .arg("--conversion-rates")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "schema version : {latest}"
+        )));
+
+    std::fs::remove_dir_all(dirpath).unwrap();
+
+    Ok(())
+}
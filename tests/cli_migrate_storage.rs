@@ -0,0 +1,174 @@
+use std::path::PathBuf;
+
+use assert_cmd::Command;
+use chrono::NaiveDate;
+use currency_conversion::{
+    common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+    storage::{
+        common::{HistoryStorageManager, Snapshot, StorageManager, StorageType},
+        sqlite::{SQLiteStorageManager, SQLiteStorageSettings},
+        tsv::{TSVStorageManager, TSVStorageSettings},
+    },
+};
+use currency_conversion_cli::config::Config;
+use predicates::prelude::predicate;
+use rust_decimal_macros::dec;
+
+#[tokio::test]
+async fn cli_migrate_storage() -> Result<(), Box<dyn std::error::Error>> {
+    let dirpath = "./temp/test/cli_migrate_storage";
+
+    std::fs::create_dir_all(dirpath).unwrap();
+
+    // source : tsv files with history
+    let symbols_settings = TSVStorageSettings {
+        file_path: PathBuf::from(dirpath.to_string() + "/symbols.tsv"),
+    };
+    let conversion_rates_settings = TSVStorageSettings {
+        file_path: PathBuf::from(dirpath.to_string() + "/conversion_rates.tsv"),
+    };
+
+    let symbols_storage_manager = TSVStorageManager::from_settings(symbols_settings.clone())?;
+    StorageManager::update(
+        &symbols_storage_manager,
+        &[Symbols {
+            code: "EUR".to_string(),
+            name: "Euro".to_string(),
+        }],
+    )
+    .await?;
+
+    let conversion_rates_storage_manager =
+        TSVStorageManager::from_settings(conversion_rates_settings.clone())?;
+    for (day, rate) in [(2, dec!(1.10)), (5, dec!(1.11))] {
+        conversion_rates_storage_manager
+            .update_snapshot(&Snapshot {
+                date: NaiveDate::from_ymd_opt(2024, 9, day).unwrap(),
+                data: vec![ConversionRate {
+                    from: "EUR".to_string(),
+                    to: "USD".to_string(),
+                    rate,
+                }],
+            })
+            .await?;
+    }
+
+    let source_config_path = dirpath.to_string() + "/source.toml";
+    confy::store_path(
+        &source_config_path,
+        Config {
+            symbols_storage: StorageType::TSV(symbols_settings),
+            conversion_rates_storage: StorageType::TSV(conversion_rates_settings),
+            api_key: "test".into(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // destination (current config) : sqlite database for conversion rates, same symbols file
+    let database_settings = SQLiteStorageSettings {
+        file_path: PathBuf::from(dirpath.to_string() + "/conversion_rates.db"),
+    };
+    let config_path = dirpath.to_string() + "/config.toml";
+    confy::store_path(
+        &config_path,
+        Config {
+            symbols_storage: StorageType::TSV(TSVStorageSettings {
+                file_path: PathBuf::from(dirpath.to_string() + "/symbols.tsv"),
+            }),
+            conversion_rates_storage: StorageType::SQLITE(database_settings.clone()),
+            api_key: "test".into(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // dry run
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("migrate-storage")
+        .arg("--all")
+        .arg("--from-config")
+        .arg(&source_config_path)
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("symbols : skipped (same storage)"))
+        .stdout(predicate::str::contains(
+            "conversion_rates : 2 snapshot(s) (2 items) to copy from TSV to SQLITE",
+        ));
+
+    let database = SQLiteStorageManager::from_settings(database_settings).await?;
+    assert!(database.get_snapshot_dates().await?.is_empty());
+
+    // copy
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("migrate-storage")
+        .arg("--conversion-rates")
+        .arg("--from-config")
+        .arg(&source_config_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "conversion_rates : 2 snapshot(s) (2 items) copied from TSV to SQLITE",
+        ));
+
+    assert_eq!(
+        database.get_snapshot_dates().await?,
+        vec![
+            NaiveDate::from_ymd_opt(2024, 9, 2).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 9, 5).unwrap()
+        ]
+    );
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--to")
+        .arg("USD")
+        .arg("--date")
+        .arg("2024-09-03")
+        .arg("10")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("11"));
+
+    // a dataset is required
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("migrate-storage")
+        .arg("--from-config")
+        .arg(&source_config_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--all"));
+
+    // missing source config isn't created
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("migrate-storage")
+        .arg("--all")
+        .arg("--from-config")
+        .arg(dirpath.to_string() + "/missing.toml")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("missing.toml not found"));
+    assert!(!PathBuf::from(dirpath.to_string() + "/missing.toml").exists());
+
+    std::fs::remove_dir_all(dirpath).unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+
+use assert_cmd::Command;
+use currency_conversion::{
+    common::{conversion_rate::ConversionRate, supported_symbols::Symbols},
+    storage::{
+        common::{StorageManager, StorageType},
+        factory::AnyStorageManager,
+        sqlite::SQLiteStorageSettings,
+        tsv::TSVStorageSettings,
+    },
+};
+use currency_conversion_cli::config::Config;
+use predicates::prelude::{predicate, PredicateBooleanExt};
+use rust_decimal_macros::dec;
+
+/// Symbols and conversion rates are stored in different storages, each command must read each dataset from its own storage
+#[tokio::test]
+async fn cli_dataset_own_storage() -> Result<(), Box<dyn std::error::Error>> {
+    let symbols = vec![
+        Symbols {
+            code: "EUR".to_string(),
+            name: "Euro".to_string(),
+        },
+        Symbols {
+            code: "USD".to_string(),
+            name: "United States Dollar".to_string(),
+        },
+    ];
+    let conversion_rates = vec![ConversionRate {
+        from: "EUR".to_string(),
+        to: "USD".to_string(),
+        rate: dec!(1.08),
+    }];
+
+    let dirpath = "./temp/test/cli_dataset_own_storage";
+
+    std::fs::create_dir_all(dirpath).unwrap();
+
+    let symbols_storage = StorageType::TSV(TSVStorageSettings {
+        file_path: PathBuf::from(dirpath.to_string() + "/symbols.tsv"),
+    });
+    let conversion_rates_storage = StorageType::SQLITE(SQLiteStorageSettings {
+        file_path: PathBuf::from(dirpath.to_string() + "/conversion_rates.db"),
+    });
+
+    AnyStorageManager::from_settings(symbols_storage.clone())
+        .await?
+        .update(&symbols)
+        .await?;
+    AnyStorageManager::from_settings(conversion_rates_storage.clone())
+        .await?
+        .update(&conversion_rates)
+        .await?;
+
+    let config_path = dirpath.to_string() + "/config.toml";
+    let config = Config {
+        symbols_storage,
+        conversion_rates_storage,
+        base: "EUR".to_string(),
+        api_key: "test".into(),
+        ..Default::default()
+    };
+
+    confy::store_path(&config_path, config).unwrap();
+
+    // list
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("list")
+        .arg("symbols")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("USD : United States Dollar"));
+
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("list")
+        .arg("conversion-rates")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("EUR -> USD : 1.08"))
+        .stdout(predicate::str::contains("Euro").not());
+
+    // info
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    let output = cmd
+        .arg("--config-path")
+        .arg(&config_path)
+        .arg("info")
+        .arg("--symbols")
+        .arg("--conversion-rates")
+        .arg("--output")
+        .arg("json")
+        .output()?;
+
+    assert!(output.status.success());
+    let infos: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(infos["symbols"]["storage"], "TSV");
+    assert_eq!(infos["symbols"]["number_of_line"], 2);
+    assert_eq!(infos["conversion_rates"]["storage"], "SQLITE");
+    assert_eq!(infos["conversion_rates"]["number_of_line"], 1);
+
+    // convert to all symbols
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("--config-path")
+        .arg(&config_path)
+        .arg("convert")
+        .arg("--from")
+        .arg("EUR")
+        .arg("--all")
+        .arg("10")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("USD : 10.8"));
+
+    std::fs::remove_dir_all(dirpath).unwrap();
+
+    Ok(())
+}
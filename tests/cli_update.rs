@@ -1,7 +1,10 @@
 use std::path::{Path, PathBuf};
 
 use assert_cmd::Command;
-use currency_conversion::storage::tsv::TSVStorageSettings;
+use currency_conversion::{
+    storage::tsv::TSVStorageSettings,
+    update::{ecb::ECBProviderSettings, provider::ProviderType},
+};
 use currency_conversion_cli::config::Config;
 use httpmock::{Method::GET, MockServer};
 use serde_json::json;
@@ -78,7 +81,7 @@ fn cli_update() -> Result<(), Box<dyn std::error::Error>> {
         latest_endpoint_url: server.url("/latest") + "?access_key={api_key}&base={base}",
         symbols_endpoint_url: server.url("/symbols") + "?access_key={api_key}",
         base: base.to_string(),
-        api_key: api_key.to_string(),
+        api_key: api_key.into(),
         conversion_rates_storage: currency_conversion::storage::common::StorageType::TSV(
             conversion_rates_tsv_settings,
         ),
@@ -130,3 +133,78 @@ fn cli_update() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn cli_update_ecb() -> Result<(), Box<dyn std::error::Error>> {
+    // server mocking
+    let server_response = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+	<gesmes:subject>Reference rates</gesmes:subject>
+	<Cube>
+		<Cube time='2024-09-05'>
+			<Cube currency='USD' rate='1.1086'/>
+			<Cube currency='JPY' rate='159.22'/>
+		</Cube>
+	</Cube>
+</gesmes:Envelope>"#;
+
+    let server = MockServer::start();
+
+    let dirpath = "./temp/test/cli_update_ecb";
+
+    std::fs::create_dir_all(dirpath).unwrap();
+
+    let mock = server.mock(|when, then| {
+        when.method(GET).path("/eurofxref-daily.xml");
+        then.status(200)
+            .header("content-type", "text/xml")
+            .body(server_response);
+    });
+
+    // modify config (no api key needed)
+    let config_path = dirpath.to_string() + "/config.toml";
+    let mut conversion_rate_path = PathBuf::new();
+    conversion_rate_path.push(dirpath.to_string() + "/conversion_rate.tsv");
+    let mut symbols_path = PathBuf::new();
+    symbols_path.push(dirpath.to_string() + "/symbols.tsv");
+    let config = Config {
+        provider: ProviderType::ECB(ECBProviderSettings {
+            url: server.url("/eurofxref-daily.xml"),
+        }),
+        conversion_rates_storage: currency_conversion::storage::common::StorageType::TSV(
+            TSVStorageSettings {
+                file_path: conversion_rate_path.clone(),
+            },
+        ),
+        symbols_storage: currency_conversion::storage::common::StorageType::TSV(
+            TSVStorageSettings {
+                file_path: symbols_path.clone(),
+            },
+        ),
+        ..Default::default()
+    };
+
+    confy::store_path(&config_path, config).unwrap();
+
+    // exec command
+    let mut cmd = Command::cargo_bin("currency-conversion-cli")?;
+
+    cmd.arg("-vvv")
+        .arg("--config-path")
+        .arg(config_path)
+        .arg("update")
+        .arg("--all")
+        .assert()
+        .success();
+
+    // feed is called for symbols and conversion rates
+    mock.assert_hits(2);
+
+    // file is created
+    assert!(Path::new(&symbols_path).exists());
+    assert!(Path::new(&conversion_rate_path).exists());
+
+    std::fs::remove_dir_all(dirpath).unwrap();
+
+    Ok(())
+}